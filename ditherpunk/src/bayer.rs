@@ -0,0 +1,119 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::seuils::vides_successifs;
+
+/// Ordre maximal accepté pour une matrice de Bayer (matrice 256×256).
+pub const ORDRE_MAX_BAYER: u32 = 8;
+
+/// Taille maximale d'une matrice de Bayer.
+pub const TAILLE_MAX_BAYER: usize = 1 << ORDRE_MAX_BAYER;
+
+/// Matrice 3×3 à partir de laquelle sont construites les matrices de taille 3·2^k.
+const BAYER_3: [[u32; 3]; 3] = [[0, 7, 3], [6, 5, 2], [4, 1, 8]];
+
+/// Erreur lors de la construction d'une matrice de Bayer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurBayer {
+    /// L'ordre dépasse [`ORDRE_MAX_BAYER`].
+    OrdreTropGrand(u32),
+    /// La taille est nulle ou dépasse [`TAILLE_MAX_BAYER`].
+    TailleInvalide(usize),
+}
+
+impl fmt::Display for ErreurBayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurBayer::OrdreTropGrand(ordre) => {
+                write!(f, "Ordre de matrice de Bayer trop grand : {} (au plus {})", ordre, ORDRE_MAX_BAYER)
+            }
+            ErreurBayer::TailleInvalide(taille) => write!(
+                f,
+                "Taille de matrice de Bayer invalide : {} (entre 1 et {})",
+                taille, TAILLE_MAX_BAYER
+            ),
+        }
+    }
+}
+
+impl Error for ErreurBayer {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Représente une matrice de Bayer, avec les fonctions pour la générer
+pub struct MatriceBayer {
+    pub taille: usize,
+    pub matrice: Vec<Vec<u32>>,
+}
+
+impl Default for MatriceBayer {
+    /// Matrice de Bayer d'ordre 3 (8×8).
+    fn default() -> Self {
+        MatriceBayer::doubler(vec![vec![0]], 3)
+    }
+}
+
+impl MatriceBayer {
+    /// Génère une matrice de Bayer d'ordre `n`, de taille 2^n
+    pub fn new_bayer_matrix(ordre: u32) -> Result<Self, ErreurBayer> {
+        if ordre > ORDRE_MAX_BAYER {
+            return Err(ErreurBayer::OrdreTropGrand(ordre));
+        }
+        Ok(MatriceBayer::doubler(vec![vec![0]], ordre))
+    }
+
+    /// Génère une matrice de tramage ordonné de taille quelconque, entre 1 et [`TAILLE_MAX_BAYER`].
+    ///
+    /// Les tailles 2^k et 3·2^k (3, 6, 12...) donnent les matrices de Bayer classiques. Pour les
+    /// autres, la partie impaire de la taille est remplie point par point, chaque valeur tombant dans
+    /// le plus grand vide laissé par les précédentes, puis doublée comme une matrice de Bayer.
+    pub fn avec_taille(taille: usize) -> Result<Self, ErreurBayer> {
+        if taille == 0 || taille > TAILLE_MAX_BAYER {
+            return Err(ErreurBayer::TailleInvalide(taille));
+        }
+        let doublements = taille.trailing_zeros();
+        let base = match taille >> doublements {
+            1 => vec![vec![0]],
+            3 => BAYER_3.iter().map(|ligne| ligne.to_vec()).collect(),
+            impaire => MatriceBayer::dispersee(impaire),
+        };
+        Ok(MatriceBayer::doubler(base, doublements))
+    }
+
+    /// Matrice de côté impair `cote` dont les valeurs 0, 1, 2... sont placées une à une dans le plus
+    /// grand vide laissé par les précédentes, comme dans la dernière phase de void-and-cluster.
+    fn dispersee(cote: usize) -> Vec<Vec<u32>> {
+        let rangs = vides_successifs(cote);
+        rangs.chunks(cote).map(|ligne| ligne.iter().map(|&rang| rang as u32).collect()).collect()
+    }
+
+    /// Double `fois` fois la taille de la matrice `base` : chaque quadrant reprend la matrice
+    /// multipliée par 4, décalée de 0 (haut gauche), 1 (bas droite), 2 (haut droit) ou 3 (bas gauche).
+    fn doubler(base: Vec<Vec<u32>>, fois: u32) -> Self {
+        let mut matrice = base;
+        for _ in 0..fois {
+            let n = matrice.len();
+            matrice = (0..2 * n)
+                .map(|y| {
+                    (0..2 * n)
+                        .map(|x| {
+                            let decalage = match (x >= n, y >= n) {
+                                (false, false) => 0,
+                                (true, true) => 1,
+                                (true, false) => 2,
+                                (false, true) => 3,
+                            };
+                            4 * matrice[y % n][x % n] + decalage
+                        })
+                        .collect()
+                })
+                .collect();
+        }
+        MatriceBayer { taille: matrice.len(), matrice }
+    }
+
+    /// Seuil normalisé du pixel `(x, y)`, centré dans son intervalle : `(valeur + 0.5) / taille²`.
+    pub fn seuil(&self, x: u32, y: u32) -> f32 {
+        let valeur = self.matrice[y as usize % self.taille][x as usize % self.taille];
+        (valeur as f32 + 0.5) / (self.taille * self.taille) as f32
+    }
+}
@@ -0,0 +1,80 @@
+//! Bruits du tramage aléatoire.
+//!
+//! Chaque bruit est centré sur 0 et s'exprime en pas de quantification : ajouté à la valeur d'un pixel
+//! avant de la quantifier, il répartit l'erreur de quantification en grain plutôt qu'en aplats.
+
+use std::str::FromStr;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Distribution du bruit du tramage aléatoire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bruit {
+    /// Bruit blanc uniforme sur un pas, équivalent à un seuil tiré au hasard.
+    #[default]
+    Uniforme,
+    /// Bruit à densité triangulaire sur deux pas (somme de deux bruits uniformes), dont la variance
+    /// ne dépend pas de la valeur du pixel.
+    Triangulaire,
+    /// Bruit gaussien d'écart type un demi-pas.
+    Gaussien,
+    /// Bruit à gradient entrelacé de Jorge Jimenez : pseudo-aléatoire, calculé à partir de la position
+    /// du pixel, avec peu de basses fréquences.
+    GradientEntrelace,
+}
+
+impl FromStr for Bruit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uniform" => Ok(Bruit::Uniforme),
+            "triangular" => Ok(Bruit::Triangulaire),
+            "gaussian" => Ok(Bruit::Gaussien),
+            "ign" => Ok(Bruit::GradientEntrelace),
+            _ => Err(format!("Bruit invalide : {} (uniform, triangular, gaussian ou ign)", s)),
+        }
+    }
+}
+
+/// Générateur d'un bruit, reproductible quand une graine est donnée.
+///
+/// Le tirage utilise ChaCha8, dont la suite, contrairement à celle de `StdRng`, est garantie d'une
+/// version de `rand` et d'une plateforme à l'autre : une même graine redonne la même image.
+pub(crate) struct GenerateurBruit {
+    bruit: Bruit,
+    rng: ChaCha8Rng,
+    /// Décalage du motif du bruit à gradient entrelacé, tiré selon la graine.
+    decalage: (u32, u32),
+}
+
+impl GenerateurBruit {
+    /// Générateur tiré de `graine`, ou d'une graine aléatoire si elle est absente.
+    pub(crate) fn new(bruit: Bruit, graine: Option<u64>) -> Self {
+        let mut rng = match graine {
+            Some(graine) => ChaCha8Rng::seed_from_u64(graine),
+            None => ChaCha8Rng::from_entropy(),
+        };
+        let decalage = (rng.gen_range(0..4096), rng.gen_range(0..4096));
+        GenerateurBruit { bruit, rng, decalage }
+    }
+
+    /// Valeur du bruit pour le pixel `(x, y)`, en pas de quantification.
+    pub(crate) fn tirer(&mut self, x: u32, y: u32) -> f32 {
+        match self.bruit {
+            Bruit::Uniforme => self.rng.gen::<f32>() - 0.5,
+            Bruit::Triangulaire => self.rng.gen::<f32>() - self.rng.gen::<f32>(),
+            Bruit::Gaussien => {
+                // Méthode de Box-Muller.
+                let u1: f32 = 1.0 - self.rng.gen::<f32>();
+                let u2: f32 = self.rng.gen();
+                0.5 * (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+            }
+            Bruit::GradientEntrelace => {
+                let (px, py) = ((x + self.decalage.0) as f32, (y + self.decalage.1) as f32);
+                (52.982_918 * (0.067_110_56 * px + 0.005_837_15 * py).fract()).fract() - 0.5
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Conversions entre sRGB et les espaces colorimétriques utilisés par la bibliothèque.
+
+use std::str::FromStr;
+
+use image::Rgb;
+
+/// Blanc de référence D65 utilisé pour CIE XYZ et CIELAB.
+const BLANC_D65: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+/// Convertit une composante sRGB (0..=255) en lumière linéaire (0.0..=1.0).
+pub fn srgb_vers_lineaire(valeur: u8) -> f32 {
+    let c = valeur as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convertit une composante en lumière linéaire (0.0..=1.0) en sRGB (0..=255).
+pub fn lineaire_vers_srgb(valeur: f32) -> u8 {
+    let c = valeur.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+/// Convertit une couleur sRGB en CIELAB (illuminant D65).
+pub fn rgb_vers_lab(couleur: &Rgb<u8>) -> [f32; 3] {
+    let r = srgb_vers_lineaire(couleur[0]);
+    let g = srgb_vers_lineaire(couleur[1]);
+    let b = srgb_vers_lineaire(couleur[2]);
+
+    let xyz = [
+        0.4124 * r + 0.3576 * g + 0.1805 * b,
+        0.2126 * r + 0.7152 * g + 0.0722 * b,
+        0.0193 * r + 0.1192 * g + 0.9505 * b,
+    ];
+
+    let f = |t: f32| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let fx = f(xyz[0] / BLANC_D65[0]);
+    let fy = f(xyz[1] / BLANC_D65[1]);
+    let fz = f(xyz[2] / BLANC_D65[2]);
+
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Convertit une couleur CIELAB (illuminant D65) en sRGB, en ramenant les couleurs hors gamut.
+pub fn lab_vers_rgb(lab: [f32; 3]) -> Rgb<u8> {
+    let fy = (lab[0] + 16.0) / 116.0;
+    let fx = fy + lab[1] / 500.0;
+    let fz = fy - lab[2] / 200.0;
+
+    let f_inv = |t: f32| {
+        if t.powi(3) > 216.0 / 24389.0 {
+            t.powi(3)
+        } else {
+            (116.0 * t - 16.0) * 27.0 / 24389.0
+        }
+    };
+    let x = f_inv(fx) * BLANC_D65[0];
+    let y = f_inv(fy) * BLANC_D65[1];
+    let z = f_inv(fz) * BLANC_D65[2];
+
+    let r = 3.2406 * x - 1.5372 * y - 0.4986 * z;
+    let g = -0.9689 * x + 1.8758 * y + 0.0415 * z;
+    let b = 0.0557 * x - 0.2040 * y + 1.0570 * z;
+
+    Rgb([lineaire_vers_srgb(r), lineaire_vers_srgb(g), lineaire_vers_srgb(b)])
+}
+
+/// Convertit une couleur CMJN (composantes entre 0.0 et 1.0) en sRGB, sans profil ICC.
+pub fn cmjn_vers_rgb(c: f32, m: f32, j: f32, n: f32) -> Rgb<u8> {
+    let composante = |v: f32| ((1.0 - v.clamp(0.0, 1.0)) * (1.0 - n.clamp(0.0, 1.0)) * 255.0).round() as u8;
+    Rgb([composante(c), composante(m), composante(j)])
+}
+
+/// Convertit une couleur TSV (teinte en degrés, saturation et valeur entre 0.0 et 1.0) en sRGB.
+pub fn tsv_vers_rgb(teinte: f32, saturation: f32, valeur: f32) -> Rgb<u8> {
+    let h = teinte.rem_euclid(360.0) / 60.0;
+    let c = valeur * saturation;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = valeur - c;
+    let composante = |v: f32| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+    Rgb([composante(r), composante(g), composante(b)])
+}
+
+/// Convertit une couleur sRGB en Oklab (Björn Ottosson, 2020).
+pub fn rgb_vers_oklab(couleur: &Rgb<u8>) -> [f32; 3] {
+    // Calcul en f64 pour garder la précision des coefficients publiés.
+    let r = srgb_vers_lineaire(couleur[0]) as f64;
+    let g = srgb_vers_lineaire(couleur[1]) as f64;
+    let b = srgb_vers_lineaire(couleur[2]) as f64;
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        (0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s) as f32,
+        (1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s) as f32,
+        (0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s) as f32,
+    ]
+}
+
+/// Luminance Rec. 709 d'un pixel sur l'échelle 0..=255.
+///
+/// Avec `lineaire`, les composantes sont d'abord converties en lumière linéaire :
+/// on obtient alors la luminance relative Y (×255) au lieu de la luma calculée
+/// sur les valeurs sRGB encodées.
+pub fn luminance(pixel: &Rgb<u8>, lineaire: bool) -> f32 {
+    let composante = |c: u8| if lineaire { srgb_vers_lineaire(c) * 255.0 } else { c as f32 };
+    0.2126 * composante(pixel[0]) + 0.7152 * composante(pixel[1]) + 0.0722 * composante(pixel[2])
+}
+
+/// Formule de conversion d'un pixel en niveau de gris, utilisée par les algorithmes en noir et blanc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Luma {
+    /// Luma Rec. 601 (télévision standard) : `0.299 R + 0.587 V + 0.114 B`.
+    Rec601,
+    /// Luma Rec. 709 (sRGB, HDTV) : `0.2126 R + 0.7152 V + 0.0722 B`.
+    #[default]
+    Rec709,
+    /// Luma Rec. 2020 (UHDTV) : `0.2627 R + 0.6780 V + 0.0593 B`.
+    Rec2020,
+    /// Moyenne des trois composantes.
+    Moyenne,
+    /// Luminosité TSL : moyenne de la plus grande et de la plus petite composante.
+    LuminositeTsl,
+    /// Valeur TSV : plus grande composante.
+    ValeurTsv,
+    /// Composante rouge seule.
+    Rouge,
+    /// Composante verte seule.
+    Vert,
+    /// Composante bleue seule.
+    Bleu,
+    /// Clarté CIE L*, ramenée sur l'échelle 0..=255.
+    ClarteCie,
+}
+
+impl FromStr for Luma {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rec601" => Ok(Luma::Rec601),
+            "rec709" => Ok(Luma::Rec709),
+            "rec2020" => Ok(Luma::Rec2020),
+            "average" => Ok(Luma::Moyenne),
+            "lightness" => Ok(Luma::LuminositeTsl),
+            "value" => Ok(Luma::ValeurTsv),
+            "red" => Ok(Luma::Rouge),
+            "green" => Ok(Luma::Vert),
+            "blue" => Ok(Luma::Bleu),
+            "lstar" => Ok(Luma::ClarteCie),
+            _ => Err(format!(
+                "Formule de gris invalide : {} (rec601, rec709, rec2020, average, lightness, value, red, green, blue ou lstar)",
+                s
+            )),
+        }
+    }
+}
+
+impl Luma {
+    /// Niveau de gris d'un pixel sur l'échelle 0..=255.
+    ///
+    /// Avec `lineaire`, les composantes sont d'abord converties en lumière linéaire, et le niveau
+    /// obtenu est lui aussi en lumière linéaire. La clarté L* étant une échelle perceptuelle, elle
+    /// donne alors la luminance relative Y dont elle découle.
+    pub fn gris(&self, pixel: &Rgb<u8>, lineaire: bool) -> f32 {
+        let composante = |c: u8| if lineaire { srgb_vers_lineaire(c) * 255.0 } else { c as f32 };
+        let [r, v, b] = pixel.0.map(composante);
+        match self {
+            Luma::Rec601 => 0.299 * r + 0.587 * v + 0.114 * b,
+            Luma::Rec709 => luminance(pixel, lineaire),
+            Luma::Rec2020 => 0.2627 * r + 0.6780 * v + 0.0593 * b,
+            Luma::Moyenne => (r + v + b) / 3.0,
+            Luma::LuminositeTsl => (r.max(v).max(b) + r.min(v).min(b)) / 2.0,
+            Luma::ValeurTsv => r.max(v).max(b),
+            Luma::Rouge => r,
+            Luma::Vert => v,
+            Luma::Bleu => b,
+            Luma::ClarteCie if lineaire => luminance(pixel, true),
+            Luma::ClarteCie => rgb_vers_lab(pixel)[0] * 2.55,
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use image::Rgb;
+use std::error::Error;
+use std::fmt;
+
+/// Erreur renvoyée lorsqu'une couleur fournie par l'utilisateur ne peut pas être lue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurCouleur {
+    /// Chaîne vide (par exemple deux virgules consécutives dans une liste).
+    Vide,
+    /// Nom de couleur absent de la liste CSS/X11.
+    NomInconnu(String),
+    /// Notation `#RGB` ou `#RRGGBB` mal formée.
+    HexInvalide(String),
+    /// Notation `rgb(r,g,b)` mal formée ou composante hors de 0..=255.
+    RgbInvalide(String),
+}
+
+impl fmt::Display for ErreurCouleur {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurCouleur::Vide => write!(f, "Couleur vide"),
+            ErreurCouleur::NomInconnu(nom) => write!(f, "Couleur non supportée : {}", nom),
+            ErreurCouleur::HexInvalide(valeur) => {
+                write!(f, "Couleur hexadécimale invalide : {} (attendu #RGB ou #RRGGBB)", valeur)
+            }
+            ErreurCouleur::RgbInvalide(valeur) => {
+                write!(f, "Couleur rgb invalide : {} (attendu rgb(r,g,b) avec 0 <= r,g,b <= 255)", valeur)
+            }
+        }
+    }
+}
+
+impl Error for ErreurCouleur {}
+
+/// Donne le nom d'une couleur s'il existe, sa notation `#rrggbb` sinon.
+pub fn rgb8_to_string(composantes: Rgb<u8>) -> String {
+    match composantes {
+        Rgb([255, 0, 0]) => "red".to_string(),
+        Rgb([0, 255, 0]) => "green".to_string(),
+        Rgb([0, 0, 255]) => "blue".to_string(),
+        Rgb([255, 255, 0]) => "yellow".to_string(),
+        Rgb([0, 255, 255]) => "cyan".to_string(),
+        Rgb([255, 0, 255]) => "magenta".to_string(),
+        Rgb([0, 0, 0]) => "black".to_string(),
+        Rgb([255, 255, 255]) => "white".to_string(),
+        Rgb([160, 82, 45]) => "sienna".to_string(),
+        Rgb([128, 0, 128]) => "purple".to_string(),
+        Rgb(rgb) => match COULEURS_NOMMEES.iter().find(|(_, valeur)| *valeur == rgb) {
+            Some((nom, _)) => nom.to_string(),
+            None => format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2]),
+        },
+    }
+}
+
+/// Lit une couleur écrite sous la forme `#RRGGBB`, `#RGB`, `rgb(r,g,b)` ou d'un nom CSS/X11.
+///
+/// Les noms sont insensibles à la casse, aux espaces, aux tirets et aux soulignés
+/// (`Light Sea-Green` vaut `lightseagreen`).
+pub fn string_to_rgb8(couleur: &str) -> Result<Rgb<u8>, ErreurCouleur> {
+    let couleur = couleur.trim();
+    if couleur.is_empty() {
+        return Err(ErreurCouleur::Vide);
+    }
+
+    if let Some(hex) = couleur.strip_prefix('#') {
+        return lire_hex(hex).ok_or_else(|| ErreurCouleur::HexInvalide(couleur.to_string()));
+    }
+
+    let minuscules = couleur.to_lowercase();
+    if let Some(composantes) = minuscules.strip_prefix("rgb(").and_then(|c| c.strip_suffix(')')) {
+        return lire_rgb(composantes).ok_or_else(|| ErreurCouleur::RgbInvalide(couleur.to_string()));
+    }
+
+    let nom: String = minuscules
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect();
+    COULEURS_NOMMEES
+        .iter()
+        .find(|(candidat, _)| *candidat == nom)
+        .map(|(_, rgb)| Rgb(*rgb))
+        .ok_or_else(|| ErreurCouleur::NomInconnu(couleur.to_string()))
+}
+
+/// Découpe une liste de couleurs séparées par des virgules, sans couper à l'intérieur de `rgb(...)`.
+pub fn decouper_couleurs(liste: &str) -> Vec<&str> {
+    let mut morceaux = Vec::new();
+    let mut profondeur = 0;
+    let mut debut = 0;
+    for (i, c) in liste.char_indices() {
+        match c {
+            '(' => profondeur += 1,
+            ')' => profondeur -= 1,
+            ',' if profondeur == 0 => {
+                morceaux.push(liste[debut..i].trim());
+                debut = i + 1;
+            }
+            _ => {}
+        }
+    }
+    morceaux.push(liste[debut..].trim());
+    morceaux
+}
+
+fn lire_hex(hex: &str) -> Option<Rgb<u8>> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, c) in hex.chars().enumerate() {
+                let chiffre = c.to_digit(16)? as u8;
+                rgb[i] = chiffre * 17;
+            }
+            Some(Rgb(rgb))
+        }
+        6 => {
+            let mut rgb = [0; 3];
+            for (i, composante) in rgb.iter_mut().enumerate() {
+                *composante = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+            }
+            Some(Rgb(rgb))
+        }
+        _ => None,
+    }
+}
+
+fn lire_rgb(composantes: &str) -> Option<Rgb<u8>> {
+    let valeurs: Vec<&str> = composantes.split(',').map(|c| c.trim()).collect();
+    if valeurs.len() != 3 {
+        return None;
+    }
+    let mut rgb = [0; 3];
+    for (composante, valeur) in rgb.iter_mut().zip(valeurs) {
+        *composante = valeur.parse().ok()?;
+    }
+    Some(Rgb(rgb))
+}
+
+pub fn euclidean_distance(color1: &Rgb<u8>, color2: &Rgb<u8>) -> f32 {
+    let r1 = color1[0] as f32;
+    let r2 = color2[0] as f32;
+    let g1 = color1[1] as f32;
+    let g2 = color2[1] as f32;
+    let b1 = color1[2] as f32;
+    let b2 = color2[2] as f32;
+    ((r2 - r1).powf(2.0) + (g2 - g1).powf(2.0) + (b2 - b1).powf(2.0)).sqrt()
+}
+
+/// Couleur de `palette` la plus proche de `pixel` en distance euclidienne sRGB.
+///
+/// Pour une autre métrique, utiliser [`Palette::plus_proche`](crate::Palette::plus_proche).
+pub fn couleur_la_plus_proche(pixel: &Rgb<u8>, palette: &[Rgb<u8>]) -> Rgb<u8> {
+    let mut min_d = f32::MAX;
+    let mut min_couleur = Rgb([0, 0, 0]);
+    for couleur in palette {
+        let d = euclidean_distance(pixel, couleur);
+        if d < min_d {
+            min_d = d;
+            min_couleur = *couleur;
+        }
+    }
+    min_couleur
+}
+
+/// Couleurs nommées CSS, complétées par les noms propres à X11.
+///
+/// `green` garde la valeur X11 `#00ff00` utilisée depuis le début par l'outil ;
+/// le vert CSS `#008000` est disponible sous le nom `webgreen`.
+const COULEURS_NOMMEES: &[(&str, [u8; 3])] = &[
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 255, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrod", [238, 221, 130]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslateblue", [132, 112, 255]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("navyblue", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("violetred", [208, 32, 144]),
+    ("webgray", [128, 128, 128]),
+    ("webgreen", [0, 128, 0]),
+    ("webgrey", [128, 128, 128]),
+    ("webmaroon", [128, 0, 0]),
+    ("webpurple", [128, 0, 128]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("x11gray", [190, 190, 190]),
+    ("x11green", [0, 255, 0]),
+    ("x11grey", [190, 190, 190]),
+    ("x11maroon", [176, 48, 96]),
+    ("x11purple", [160, 32, 240]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
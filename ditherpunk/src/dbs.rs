@@ -0,0 +1,235 @@
+//! Recherche binaire directe (Direct Binary Search, DBS) d'Analoui et Allebach.
+//!
+//! Partant d'une image déjà tramée en noir et blanc, chaque pixel est basculé, ou échangé avec un
+//! voisin de couleur opposée, si cela réduit l'erreur perçue : l'écart entre l'image tramée et
+//! l'originale, filtré par un modèle gaussien du système visuel humain. Les passes se répètent
+//! jusqu'à ce qu'aucun pixel ne change, ou jusqu'au nombre d'itérations demandé.
+
+use image::{Rgb, RgbImage};
+
+use crate::conversion::luminance;
+use crate::ditherer::{Ditherer, Reglages};
+use crate::palette::Palette;
+
+/// Nombre maximal de passes par défaut.
+pub const ITERATIONS_DBS: usize = 10;
+
+/// Écart type, en pixels, du filtre gaussien modélisant le système visuel.
+const SIGMA_VISUEL: f32 = 1.5;
+
+/// Palette passée à l'algorithme de départ : blanc puis noir, car les seuillages comme `mono`
+/// donnent leur première couleur aux pixels clairs.
+pub fn palette_depart() -> Palette {
+    Palette::new(vec![Rgb([255, 255, 255]), Rgb([0, 0, 0])])
+}
+
+/// Voisins avec lesquels un pixel peut être échangé.
+const VOISINS: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// Bilan d'une passe sur l'image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PasseDbs {
+    /// Pixels inversés seuls.
+    pub basculements: usize,
+    /// Paires de pixels voisins échangés.
+    pub echanges: usize,
+    /// Erreur perçue moyenne par pixel après la passe.
+    pub erreur: f64,
+}
+
+/// Suivi de la convergence de la recherche.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RapportDbs {
+    /// Erreur perçue moyenne par pixel de l'image de départ.
+    pub erreur_initiale: f64,
+    pub passes: Vec<PasseDbs>,
+}
+
+/// Autocorrélation du filtre visuel, qui est une gaussienne d'écart type `SIGMA_VISUEL · √2`.
+struct Autocorrelation {
+    rayon: i32,
+    valeurs: Vec<f32>,
+}
+
+impl Autocorrelation {
+    fn new() -> Self {
+        let rayon = (3.0 * SIGMA_VISUEL * std::f32::consts::SQRT_2).ceil() as i32;
+        let valeurs = (-rayon..=rayon)
+            .flat_map(|dy| (-rayon..=rayon).map(move |dx| (dx, dy)))
+            .map(|(dx, dy)| (-((dx * dx + dy * dy) as f32) / (4.0 * SIGMA_VISUEL * SIGMA_VISUEL)).exp())
+            .collect();
+        Autocorrelation { rayon, valeurs }
+    }
+
+    fn get(&self, dx: i32, dy: i32) -> f32 {
+        if dx.abs() > self.rayon || dy.abs() > self.rayon {
+            return 0.0;
+        }
+        let cote = 2 * self.rayon + 1;
+        self.valeurs[((dy + self.rayon) * cote + dx + self.rayon) as usize]
+    }
+}
+
+/// État de la recherche : image binaire, erreur et erreur corrélée par le filtre visuel.
+struct Recherche {
+    largeur: i32,
+    hauteur: i32,
+    filtre: Autocorrelation,
+    /// `true` pour un pixel blanc.
+    binaire: Vec<bool>,
+    /// Écart entre l'image binaire et l'originale, entre -1 et 1.
+    erreur: Vec<f32>,
+    /// Erreur corrélée : somme des erreurs voisines pondérées par l'autocorrélation du filtre.
+    correlee: Vec<f32>,
+}
+
+impl Recherche {
+    fn indice(&self, x: i32, y: i32) -> usize {
+        (y * self.largeur + x) as usize
+    }
+
+    fn contient(&self, x: i32, y: i32) -> bool {
+        (0..self.largeur).contains(&x) && (0..self.hauteur).contains(&y)
+    }
+
+    /// Ajoute `a` à l'erreur du pixel `(x, y)` et met à jour l'erreur corrélée autour de lui.
+    fn deposer(&mut self, x: i32, y: i32, a: f32) {
+        let indice = self.indice(x, y);
+        self.erreur[indice] += a;
+        let rayon = self.filtre.rayon;
+        for vy in (y - rayon).max(0)..(y + rayon + 1).min(self.hauteur) {
+            for vx in (x - rayon).max(0)..(x + rayon + 1).min(self.largeur) {
+                let voisin = self.indice(vx, vy);
+                self.correlee[voisin] += a * self.filtre.get(vx - x, vy - y);
+            }
+        }
+    }
+
+    /// Inverse le pixel `(x, y)`, `a` valant 1 s'il devient blanc et -1 s'il devient noir.
+    fn basculer(&mut self, x: i32, y: i32, a: f32) {
+        let indice = self.indice(x, y);
+        self.binaire[indice] = !self.binaire[indice];
+        self.deposer(x, y, a);
+    }
+
+    /// Erreur perçue moyenne par pixel.
+    fn erreur_percue(&self) -> f64 {
+        let total: f64 = self.erreur.iter().zip(&self.correlee).map(|(&e, &c)| e as f64 * c as f64).sum();
+        total / self.erreur.len().max(1) as f64
+    }
+
+    /// Essaie chaque pixel une fois, en gardant le changement qui réduit le plus l'erreur perçue.
+    fn passe(&mut self) -> PasseDbs {
+        let mut passe = PasseDbs { basculements: 0, echanges: 0, erreur: 0.0 };
+        let centre = self.filtre.get(0, 0);
+        for y in 0..self.hauteur {
+            for x in 0..self.largeur {
+                let indice = self.indice(x, y);
+                let a0 = if self.binaire[indice] { -1.0 } else { 1.0 };
+                // Variation d'erreur d'un basculement, puis des échanges avec un voisin opposé.
+                let mut meilleur = (centre + 2.0 * a0 * self.correlee[indice], None);
+                for (dx, dy) in VOISINS {
+                    let (vx, vy) = (x + dx, y + dy);
+                    if !self.contient(vx, vy) || self.binaire[self.indice(vx, vy)] == self.binaire[indice] {
+                        continue;
+                    }
+                    let voisin = self.indice(vx, vy);
+                    let variation = 2.0 * centre - 2.0 * self.filtre.get(dx, dy)
+                        + 2.0 * a0 * (self.correlee[indice] - self.correlee[voisin]);
+                    if variation < meilleur.0 {
+                        meilleur = (variation, Some((vx, vy)));
+                    }
+                }
+
+                if meilleur.0 < 0.0 {
+                    self.basculer(x, y, a0);
+                    match meilleur.1 {
+                        Some((vx, vy)) => {
+                            self.basculer(vx, vy, -a0);
+                            passe.echanges += 1;
+                        }
+                        None => passe.basculements += 1,
+                    }
+                }
+            }
+        }
+        passe.erreur = self.erreur_percue();
+        passe
+    }
+}
+
+/// Affine par recherche binaire directe l'image noir et blanc `depart`, tramage de `img`.
+///
+/// Au plus `iterations` passes sont faites, la recherche s'arrêtant dès qu'une passe ne change plus
+/// aucun pixel. Un pixel de `depart` est blanc si sa luminance dépasse 128. Avec `reglages.lineaire`,
+/// l'image est comparée en lumière linéaire.
+///
+/// # Panics
+///
+/// Si `depart` n'a pas les dimensions de `img`.
+pub fn recherche_binaire_directe(
+    img: &RgbImage,
+    depart: &RgbImage,
+    iterations: usize,
+    reglages: &Reglages,
+) -> (RgbImage, RapportDbs) {
+    assert_eq!(img.dimensions(), depart.dimensions(), "l'image de départ doit avoir les dimensions de l'image");
+    let (largeur, hauteur) = img.dimensions();
+    let binaire: Vec<bool> = depart.pixels().map(|pixel| luminance(pixel, false) > 128.0).collect();
+    let erreur: Vec<f32> = img
+        .pixels()
+        .zip(&binaire)
+        .map(|(pixel, &blanc)| f32::from(u8::from(blanc)) - reglages.gris(pixel) / 255.0)
+        .collect();
+
+    let mut recherche = Recherche {
+        largeur: largeur as i32,
+        hauteur: hauteur as i32,
+        filtre: Autocorrelation::new(),
+        binaire,
+        erreur: vec![0.0; erreur.len()],
+        correlee: vec![0.0; erreur.len()],
+    };
+    for y in 0..recherche.hauteur {
+        for x in 0..recherche.largeur {
+            recherche.deposer(x, y, erreur[recherche.indice(x, y)]);
+        }
+    }
+
+    let mut rapport = RapportDbs { erreur_initiale: recherche.erreur_percue(), passes: Vec::new() };
+    for _ in 0..iterations {
+        let passe = recherche.passe();
+        rapport.passes.push(passe);
+        if passe.basculements + passe.echanges == 0 {
+            break;
+        }
+    }
+
+    let sortie = RgbImage::from_fn(largeur, hauteur, |x, y| {
+        if recherche.binaire[recherche.indice(x as i32, y as i32)] {
+            Rgb([255, 255, 255])
+        } else {
+            Rgb([0, 0, 0])
+        }
+    });
+    (sortie, rapport)
+}
+
+/// Recherche binaire directe en noir et blanc, partant de la sortie de `depart` en noir et blanc.
+/// La palette est ignorée.
+pub struct RechercheBinaireDirecte {
+    pub depart: Box<dyn Ditherer>,
+    pub iterations: usize,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for RechercheBinaireDirecte {
+    fn nom(&self) -> &str {
+        "dbs"
+    }
+
+    fn dither(&self, img: &RgbImage, _palette: &Palette) -> RgbImage {
+        let depart = self.depart.dither(img, &palette_depart());
+        recherche_binaire_directe(img, &depart, self.iterations, &self.reglages).0
+    }
+}
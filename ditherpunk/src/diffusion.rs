@@ -0,0 +1,345 @@
+use image::{Rgb, RgbImage};
+
+use crate::ditherer::{Ditherer, Reglages};
+use crate::niveaux::NiveauxGris;
+use crate::palette::Palette;
+use crate::tampon::TamponErreur;
+
+/// Noyau de diffusion d'erreur : poids, diviseur et position du pixel courant.
+///
+/// Le pixel courant doit précéder, dans l'ordre de parcours, tous les poids non nuls :
+/// les lignes au-dessus de l'ancre et les cases à sa gauche sur sa ligne sont nulles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Noyau {
+    pub poids: &'static [&'static [i32]],
+    pub diviseur: i32,
+    /// Position `(colonne, ligne)` du pixel courant dans `poids`.
+    pub ancre: (usize, usize),
+}
+
+/// Noyau de Jarvis-Judice-Ninke.
+pub const JARVIS_JUDICE_NINKE: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 7, 5], &[3, 5, 7, 5, 3], &[1, 3, 5, 3, 1]],
+    diviseur: 48,
+    ancre: (2, 0),
+};
+
+/// Noyau d'Atkinson : seuls les 6/8 de l'erreur sont diffusés.
+pub const ATKINSON: Noyau = Noyau {
+    poids: &[&[0, 0, 1, 1], &[1, 1, 1, 0], &[0, 1, 0, 0]],
+    diviseur: 8,
+    ancre: (1, 0),
+};
+
+/// Noyau de Stucki.
+pub const STUCKI: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 8, 4], &[2, 4, 8, 4, 2], &[1, 2, 4, 2, 1]],
+    diviseur: 42,
+    ancre: (2, 0),
+};
+
+/// Noyau de Burkes.
+pub const BURKES: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 8, 4], &[2, 4, 8, 4, 2]],
+    diviseur: 32,
+    ancre: (2, 0),
+};
+
+/// Noyau de Sierra sur trois lignes.
+pub const SIERRA_3: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 5, 3], &[2, 4, 5, 4, 2], &[0, 2, 3, 2, 0]],
+    diviseur: 32,
+    ancre: (2, 0),
+};
+
+/// Noyau de Sierra sur deux lignes.
+pub const SIERRA_2: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 4, 3], &[1, 2, 3, 2, 1]],
+    diviseur: 16,
+    ancre: (2, 0),
+};
+
+/// Noyau « Sierra Lite ».
+pub const SIERRA_LITE: Noyau = Noyau {
+    poids: &[&[0, 0, 2], &[1, 1, 0]],
+    diviseur: 4,
+    ancre: (1, 0),
+};
+
+/// Noyau de Fan.
+pub const FAN: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 7], &[1, 3, 5, 0]],
+    diviseur: 16,
+    ancre: (2, 0),
+};
+
+/// Noyau de Shiau-Fan à quatre voisins.
+pub const SHIAU_FAN: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 4], &[1, 1, 2, 0]],
+    diviseur: 8,
+    ancre: (2, 0),
+};
+
+/// Noyau de Shiau-Fan à cinq voisins.
+pub const SHIAU_FAN_2: Noyau = Noyau {
+    poids: &[&[0, 0, 0, 0, 8], &[1, 1, 2, 4, 0]],
+    diviseur: 16,
+    ancre: (3, 0),
+};
+
+/// Noyau de Stevenson-Arce.
+///
+/// Les voisins sont disposés en quinconce (grille hexagonale), d'où les zéros intercalés.
+pub const STEVENSON_ARCE: Noyau = Noyau {
+    poids: &[
+        &[0, 0, 0, 0, 0, 32, 0],
+        &[12, 0, 26, 0, 30, 0, 16],
+        &[0, 12, 0, 26, 0, 12, 0],
+        &[5, 0, 12, 0, 12, 0, 5],
+    ],
+    diviseur: 200,
+    ancre: (3, 0),
+};
+
+/// Voisin recevant une part de l'erreur : décalage horizontal, décalage vertical et poids.
+pub(crate) type Voisin = (i32, i32, f32);
+
+/// Voisins de la diffusion d'erreur simple : moitié à droite, moitié en dessous.
+const VOISINS_SIMPLE: &[Voisin] = &[(1, 0, 0.5), (0, 1, 0.5)];
+
+/// Voisins de la diffusion de Floyd-Steinberg.
+const VOISINS_FLOYD_STEINBERG: &[Voisin] = &[(1, 0, 7.0 / 16.0), (-1, 1, 3.0 / 16.0), (0, 1, 5.0 / 16.0), (1, 1, 1.0 / 16.0)];
+
+/// Parcourt l'image ligne par ligne sur un [`TamponErreur`] : chaque pixel est remplacé par
+/// `quantifier(valeur)` et l'erreur commise est répartie entre les `voisins(x, y)` du pixel.
+/// Avec `reglages.serpentin`, une ligne sur deux est parcourue en sens inverse.
+pub(crate) fn diffuser<V: AsRef<[Voisin]>>(
+    mut tampon: TamponErreur,
+    mut voisins: impl FnMut(u32, u32) -> V,
+    reglages: &Reglages,
+    mut quantifier: impl FnMut(&TamponErreur, u32, u32) -> Rgb<u8>,
+) -> RgbImage {
+    let (largeur, hauteur) = tampon.dimensions();
+    let mut sortie = RgbImage::new(largeur, hauteur);
+
+    for y in 0..hauteur {
+        // En parcours serpentin, les lignes impaires sont lues de droite à gauche
+        // et le noyau est retourné horizontalement.
+        let retourne = reglages.serpentin && y % 2 == 1;
+        let sens = if retourne { -1 } else { 1 };
+
+        for i in 0..largeur {
+            let x = if retourne { largeur - 1 - i } else { i };
+            let couleur = quantifier(&tampon, x, y);
+            let erreur = tampon.erreur(x, y, &couleur);
+            sortie.put_pixel(x, y, couleur);
+
+            for &(dx, dy, poids) in voisins(x, y).as_ref() {
+                tampon.ajouter(x as i64 + (sens * dx) as i64, y as i64 + dy as i64, &erreur, poids, reglages.ecretage);
+            }
+        }
+    }
+
+    sortie
+}
+
+/// Diffusion d'erreur sur la palette, vers les `voisins` donnés.
+fn diffuser_palette(img: &RgbImage, palette: &Palette, voisins: &[Voisin], reglages: &Reglages) -> RgbImage {
+    let tampon = TamponErreur::depuis_image(img, reglages.lineaire);
+    diffuser(tampon, |_, _| voisins, reglages, |tampon, x, y| palette.plus_proche(&tampon.get_srgb(x, y)))
+}
+
+/// Diffusion d'erreur simple en noir et blanc : la moitié de l'erreur part à droite, l'autre moitié en dessous.
+///
+/// Avec `reglages.lineaire`, la luminance et l'erreur sont calculées en lumière linéaire.
+pub fn diffusion_d_erreur_simple(img: &RgbImage, reglages: &Reglages) -> RgbImage {
+    diffusion_d_erreur_simple_niveaux(img, &NiveauxGris::default(), reglages)
+}
+
+/// Diffusion d'erreur simple en N niveaux de gris : chaque pixel prend le niveau le plus proche de sa
+/// luminance corrigée par l'erreur reçue.
+///
+/// Avec `reglages.lineaire`, la luminance et l'erreur sont calculées en lumière linéaire.
+pub fn diffusion_d_erreur_simple_niveaux(img: &RgbImage, niveaux: &NiveauxGris, reglages: &Reglages) -> RgbImage {
+    let tampon = TamponErreur::depuis_image_gris(img, reglages.lineaire, |pixel| reglages.gris(pixel));
+    diffuser(tampon, |_, _| VOISINS_SIMPLE, reglages, |tampon, x, y| {
+        let gris = niveaux.plus_proche(tampon.get(x, y)[0], reglages.lineaire);
+        Rgb([gris, gris, gris])
+    })
+}
+
+/// Diffusion d'erreur simple vers la couleur la plus proche de la palette.
+pub fn diffusion_d_erreur_simple_palette(img: &RgbImage, palette: &Palette, reglages: &Reglages) -> RgbImage {
+    diffuser_palette(img, palette, VOISINS_SIMPLE, reglages)
+}
+
+/// Diffusion d'erreur de Floyd-Steinberg vers la couleur la plus proche de la palette.
+pub fn diffusion_d_erreur_floyd_steinberg_palette(img: &RgbImage, palette: &Palette, reglages: &Reglages) -> RgbImage {
+    diffuser_palette(img, palette, VOISINS_FLOYD_STEINBERG, reglages)
+}
+
+/// Diffusion d'erreur générique : l'erreur est répartie selon `diffusion_matrix`, chaque poids étant divisé par `factor`.
+///
+/// Le pixel courant est à la position `ancre = (colonne, ligne)` de la matrice.
+pub fn diffusion_d_erreur_palette_matrice(
+    img: &RgbImage,
+    palette: &Palette,
+    diffusion_matrix: &[impl AsRef<[i32]>],
+    factor: i32,
+    ancre: (usize, usize),
+    reglages: &Reglages,
+) -> RgbImage {
+    let (colonne, ligne) = (ancre.0 as i32, ancre.1 as i32);
+    let mut voisins = Vec::new();
+    for (dy, row) in diffusion_matrix.iter().map(|r| r.as_ref()).enumerate() {
+        for (dx, weight) in row.iter().enumerate() {
+            if *weight != 0 {
+                voisins.push((dx as i32 - colonne, dy as i32 - ligne, *weight as f32 / factor as f32));
+            }
+        }
+    }
+    diffuser_palette(img, palette, &voisins, reglages)
+}
+
+/// Diffusion d'erreur simple en niveaux de gris, noir et blanc par défaut, la palette est ignorée.
+#[derive(Debug, Clone, Default)]
+pub struct DiffusionSimple {
+    pub niveaux: NiveauxGris,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for DiffusionSimple {
+    fn nom(&self) -> &str {
+        "simple"
+    }
+
+    fn dither(&self, img: &RgbImage, _palette: &Palette) -> RgbImage {
+        diffusion_d_erreur_simple_niveaux(img, &self.niveaux, &self.reglages)
+    }
+}
+
+/// Diffusion d'erreur simple vers la palette.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiffusionSimplePalette {
+    pub reglages: Reglages,
+}
+
+impl Ditherer for DiffusionSimplePalette {
+    fn nom(&self) -> &str {
+        "simplePal"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        diffusion_d_erreur_simple_palette(img, palette, &self.reglages)
+    }
+}
+
+/// Diffusion d'erreur de Floyd-Steinberg vers la palette.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FloydSteinberg {
+    pub reglages: Reglages,
+}
+
+impl Ditherer for FloydSteinberg {
+    fn nom(&self) -> &str {
+        "floyd"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        diffusion_d_erreur_floyd_steinberg_palette(img, palette, &self.reglages)
+    }
+}
+
+/// Diffusion d'erreur vers la palette selon une matrice quelconque.
+#[derive(Debug, Clone)]
+pub struct DiffusionMatrice {
+    pub nom: String,
+    pub matrice: Vec<Vec<i32>>,
+    pub facteur: i32,
+    /// Position `(colonne, ligne)` du pixel courant dans la matrice.
+    pub ancre: (usize, usize),
+    pub reglages: Reglages,
+}
+
+impl DiffusionMatrice {
+    pub fn new(nom: &str, noyau: &Noyau) -> Self {
+        DiffusionMatrice {
+            nom: nom.to_string(),
+            matrice: noyau.poids.iter().map(|ligne| ligne.to_vec()).collect(),
+            facteur: noyau.diviseur,
+            ancre: noyau.ancre,
+            reglages: Reglages::default(),
+        }
+    }
+
+    pub fn jarvis_judice_ninke() -> Self {
+        DiffusionMatrice::new("jjn", &JARVIS_JUDICE_NINKE)
+    }
+
+    pub fn atkinson() -> Self {
+        DiffusionMatrice::new("atkinson", &ATKINSON)
+    }
+
+    pub fn stucki() -> Self {
+        DiffusionMatrice::new("stucki", &STUCKI)
+    }
+
+    pub fn burkes() -> Self {
+        DiffusionMatrice::new("burkes", &BURKES)
+    }
+
+    pub fn sierra_3() -> Self {
+        DiffusionMatrice::new("sierra3", &SIERRA_3)
+    }
+
+    pub fn sierra_2() -> Self {
+        DiffusionMatrice::new("sierra2", &SIERRA_2)
+    }
+
+    pub fn sierra_lite() -> Self {
+        DiffusionMatrice::new("sierraLite", &SIERRA_LITE)
+    }
+
+    pub fn fan() -> Self {
+        DiffusionMatrice::new("fan", &FAN)
+    }
+
+    pub fn shiau_fan() -> Self {
+        DiffusionMatrice::new("shiauFan", &SHIAU_FAN)
+    }
+
+    pub fn shiau_fan_2() -> Self {
+        DiffusionMatrice::new("shiauFan2", &SHIAU_FAN_2)
+    }
+
+    pub fn stevenson_arce() -> Self {
+        DiffusionMatrice::new("stevensonArce", &STEVENSON_ARCE)
+    }
+
+    /// Tous les noyaux classiques fournis par la bibliothèque.
+    pub fn catalogue() -> Vec<Self> {
+        vec![
+            DiffusionMatrice::jarvis_judice_ninke(),
+            DiffusionMatrice::atkinson(),
+            DiffusionMatrice::stucki(),
+            DiffusionMatrice::burkes(),
+            DiffusionMatrice::sierra_3(),
+            DiffusionMatrice::sierra_2(),
+            DiffusionMatrice::sierra_lite(),
+            DiffusionMatrice::fan(),
+            DiffusionMatrice::shiau_fan(),
+            DiffusionMatrice::shiau_fan_2(),
+            DiffusionMatrice::stevenson_arce(),
+        ]
+    }
+}
+
+impl Ditherer for DiffusionMatrice {
+    fn nom(&self) -> &str {
+        &self.nom
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        diffusion_d_erreur_palette_matrice(img, palette, &self.matrice, self.facteur, self.ancre, &self.reglages)
+    }
+}
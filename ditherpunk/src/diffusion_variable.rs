@@ -0,0 +1,283 @@
+//! Diffusion d'erreur à coefficients variables : les poids dépendent de l'intensité du pixel.
+//!
+//! - Ostromoukhov (2001) : une table de coefficients par niveau de gris, optimisée pour
+//!   limiter les motifs réguliers dans les tons moyens,
+//! - Zhou et Fang (2003) : des coefficients interpolés entre des niveaux clés, complétés
+//!   par une modulation aléatoire du seuil.
+//!
+//! Les deux méthodes répartissent l'erreur entre trois voisins : à droite, en bas à gauche et en bas.
+//! Sur une palette, l'intensité utilisée est la luminance du pixel d'origine.
+
+use image::{Rgb, RgbImage};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::diffusion::{diffuser, Voisin};
+use crate::ditherer::{Ditherer, Reglages};
+use crate::palette::Palette;
+use crate::tampon::TamponErreur;
+
+/// Table d'Ostromoukhov pour les intensités 0 à 127 : poids à droite, en bas à gauche,
+/// en bas, puis leur somme. Les intensités 128 à 255 utilisent la table en miroir.
+///
+/// Recopiée du code d'accompagnement de l'article ; les intensités 95 à 107 y partagent bien les
+/// mêmes coefficients `[5, 3, 2, 10]`.
+pub const OSTROMOUKHOV: [[i32; 4]; 128] = [
+    [13, 0, 5, 18], [13, 0, 5, 18], [21, 0, 10, 31], [7, 0, 4, 11],
+    [8, 0, 5, 13], [47, 3, 28, 78], [23, 3, 13, 39], [15, 3, 8, 26],
+    [22, 6, 11, 39], [43, 15, 20, 78], [7, 3, 3, 13], [501, 224, 211, 936],
+    [249, 116, 103, 468], [165, 80, 67, 312], [123, 62, 49, 234], [489, 256, 191, 936],
+    [81, 44, 31, 156], [483, 272, 181, 936], [60, 35, 22, 117], [53, 32, 19, 104],
+    [237, 148, 83, 468], [471, 304, 161, 936], [3, 2, 1, 6], [481, 314, 185, 980],
+    [354, 226, 155, 735], [1389, 866, 685, 2940], [227, 138, 125, 490], [267, 158, 163, 588],
+    [327, 188, 220, 735], [61, 34, 45, 140], [627, 338, 505, 1470], [1227, 638, 1075, 2940],
+    [20, 10, 19, 49], [1937, 1000, 1767, 4704], [977, 520, 855, 2352], [657, 360, 551, 1568],
+    [71, 40, 57, 168], [2005, 1160, 1539, 4704], [337, 200, 247, 784], [2039, 1240, 1425, 4704],
+    [257, 160, 171, 588], [691, 440, 437, 1568], [1045, 680, 627, 2352], [301, 200, 171, 672],
+    [177, 120, 95, 392], [2141, 1480, 1083, 4704], [1079, 760, 513, 2352], [725, 520, 323, 1568],
+    [137, 100, 57, 294], [2209, 1640, 855, 4704], [53, 40, 19, 112], [2243, 1720, 741, 4704],
+    [565, 440, 171, 1176], [759, 600, 209, 1568], [1147, 920, 285, 2352], [2311, 1880, 513, 4704],
+    [97, 80, 19, 196], [335, 280, 57, 672], [1181, 1000, 171, 2352], [793, 680, 95, 1568],
+    [599, 520, 57, 1176], [2413, 2120, 171, 4704], [405, 360, 19, 784], [2447, 2200, 57, 4704],
+    [11, 10, 0, 21], [158, 151, 3, 312], [178, 179, 7, 364], [1030, 1091, 63, 2184],
+    [248, 277, 21, 546], [318, 375, 35, 728], [458, 571, 63, 1092], [878, 1159, 147, 2184],
+    [5, 7, 1, 13], [172, 181, 37, 390], [97, 76, 22, 195], [72, 41, 17, 130],
+    [119, 47, 29, 195], [4, 1, 1, 6], [4, 1, 1, 6], [4, 1, 1, 6],
+    [4, 1, 1, 6], [4, 1, 1, 6], [4, 1, 1, 6], [4, 1, 1, 6],
+    [4, 1, 1, 6], [4, 1, 1, 6], [65, 18, 17, 100], [95, 29, 26, 150],
+    [185, 62, 53, 300], [30, 11, 9, 50], [35, 14, 11, 60], [85, 37, 28, 150],
+    [55, 26, 19, 100], [80, 41, 29, 150], [155, 86, 59, 300], [5, 3, 2, 10],
+    [5, 3, 2, 10], [5, 3, 2, 10], [5, 3, 2, 10], [5, 3, 2, 10],
+    [5, 3, 2, 10], [5, 3, 2, 10], [5, 3, 2, 10], [5, 3, 2, 10],
+    [5, 3, 2, 10], [5, 3, 2, 10], [5, 3, 2, 10], [5, 3, 2, 10],
+    [305, 176, 119, 600], [155, 86, 59, 300], [105, 56, 39, 200], [80, 41, 29, 150],
+    [65, 32, 23, 120], [55, 26, 19, 100], [335, 152, 113, 600], [85, 37, 28, 150],
+    [115, 48, 37, 200], [35, 14, 11, 60], [355, 136, 109, 600], [30, 11, 9, 50],
+    [365, 128, 107, 600], [185, 62, 53, 300], [25, 8, 7, 40], [95, 29, 26, 150],
+    [385, 112, 103, 600], [65, 18, 17, 100], [395, 104, 101, 600], [4, 1, 1, 6],
+];
+
+/// Niveaux clés de Zhou-Fang : intensité, poids à droite, en bas à gauche et en bas.
+/// Les coefficients des autres intensités sont interpolés linéairement, après normalisation.
+pub const ZHOU_FANG_COEFFICIENTS: &[(u8, [i32; 3])] = &[
+    (0, [13, 0, 5]),
+    (1, [1300249, 0, 499250]),
+    (2, [213113, 287, 99357]),
+    (3, [351854, 0, 199965]),
+    (4, [801100, 0, 490999]),
+    (10, [704075, 297466, 303694]),
+    (22, [46613, 31917, 21469]),
+    (32, [47482, 30617, 21900]),
+    (44, [43024, 42131, 14826]),
+    (64, [36411, 43219, 20369]),
+    (72, [38477, 53843, 7678]),
+    (77, [40503, 51547, 7948]),
+    (85, [35865, 34108, 30026]),
+    (95, [34117, 36899, 28983]),
+    (102, [35464, 35049, 29485]),
+    (107, [16477, 18810, 14712]),
+    (112, [33360, 37954, 28685]),
+    (127, [35269, 36066, 28664]),
+];
+
+/// Niveaux clés de Zhou-Fang : intensité et force de la modulation du seuil (entre 0 et 1).
+pub const ZHOU_FANG_MODULATION: &[(u8, f32)] = &[
+    (0, 0.0),
+    (44, 0.34),
+    (64, 0.5),
+    (85, 1.0),
+    (95, 0.17),
+    (102, 0.5),
+    (107, 0.7),
+    (112, 0.79),
+    (127, 1.0),
+];
+
+/// Amplitude maximale de la modulation du seuil, sur l'échelle 0..=255.
+const AMPLITUDE_MODULATION: f32 = 128.0;
+
+/// Ramène une intensité 0..=255 sur la moitié 0..=127 des tables, symétriques autour de 127,5.
+fn niveau(intensite: f32) -> usize {
+    let intensite = intensite.round().clamp(0.0, 255.0) as usize;
+    intensite.min(255 - intensite)
+}
+
+/// Les trois voisins de la diffusion à coefficients variables, pour des poids normalisés.
+fn voisins([droite, bas_gauche, bas]: [f32; 3]) -> [Voisin; 3] {
+    [(1, 0, droite), (-1, 1, bas_gauche), (0, 1, bas)]
+}
+
+/// Interpole linéairement, pour chaque niveau 0..=127, une valeur définie aux niveaux clés.
+fn interpoler<T: Copy>(cles: &[(u8, T)], valeur: impl Fn(T) -> [f32; 3]) -> [[f32; 3]; 128] {
+    let mut table = [[0.0; 3]; 128];
+    for paire in cles.windows(2) {
+        let ((debut, a), (fin, b)) = (paire[0], paire[1]);
+        let (a, b) = (valeur(a), valeur(b));
+        for n in debut..=fin {
+            let t = (n - debut) as f32 / (fin - debut) as f32;
+            table[n as usize] = [0, 1, 2].map(|c| a[c] + (b[c] - a[c]) * t);
+        }
+    }
+    table
+}
+
+/// Intensité du pixel d'origine, qui choisit les coefficients de diffusion.
+fn intensites(img: &RgbImage, reglages: &Reglages) -> Vec<usize> {
+    img.pixels().map(|pixel| niveau(reglages.gris(pixel))).collect()
+}
+
+/// Diffusion d'erreur d'Ostromoukhov vers la couleur la plus proche de la palette.
+pub fn diffusion_d_erreur_ostromoukhov_palette(img: &RgbImage, palette: &Palette, reglages: &Reglages) -> RgbImage {
+    let coefficients = OSTROMOUKHOV.map(|[droite, bas_gauche, bas, somme]| {
+        voisins([droite, bas_gauche, bas].map(|poids| poids as f32 / somme as f32))
+    });
+    let intensites = intensites(img, reglages);
+    let largeur = img.width();
+
+    let tampon = TamponErreur::depuis_image(img, reglages.lineaire);
+    diffuser(
+        tampon,
+        |x, y| coefficients[intensites[(y * largeur + x) as usize]],
+        reglages,
+        |tampon, x, y| palette.plus_proche(&tampon.get_srgb(x, y)),
+    )
+}
+
+/// Diffusion d'erreur de Zhou-Fang vers la couleur la plus proche de la palette.
+///
+/// Le seuil est modulé en ajoutant à la valeur comparée à la palette un bruit uniforme,
+/// d'amplitude proportionnelle à la force de modulation du niveau ; l'erreur diffusée reste
+/// calculée sur la valeur non bruitée. Le bruit est tiré avec une graine fixe.
+pub fn diffusion_d_erreur_zhou_fang_palette(img: &RgbImage, palette: &Palette, reglages: &Reglages) -> RgbImage {
+    let coefficients = interpoler(ZHOU_FANG_COEFFICIENTS, |poids| {
+        let somme: i32 = poids.iter().sum();
+        poids.map(|p| p as f32 / somme as f32)
+    })
+    .map(voisins);
+    let modulation = interpoler(ZHOU_FANG_MODULATION, |force| [force; 3]).map(|[force, _, _]| force);
+    let intensites = intensites(img, reglages);
+    let largeur = img.width();
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+    let tampon = TamponErreur::depuis_image(img, reglages.lineaire);
+    diffuser(
+        tampon,
+        |x, y| coefficients[intensites[(y * largeur + x) as usize]],
+        reglages,
+        |tampon, x, y| {
+            let force = modulation[intensites[(y * largeur + x) as usize]];
+            let bruit = rng.gen_range(-0.5..0.5) * AMPLITUDE_MODULATION * force;
+            let valeur = tampon.get_srgb(x, y).0.map(|c| (c as f32 + bruit).round().clamp(0.0, 255.0) as u8);
+            palette.plus_proche(&Rgb(valeur))
+        },
+    )
+}
+
+/// Diffusion d'erreur d'Ostromoukhov vers la palette.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ostromoukhov {
+    pub reglages: Reglages,
+}
+
+impl Ditherer for Ostromoukhov {
+    fn nom(&self) -> &str {
+        "ostromoukhov"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        diffusion_d_erreur_ostromoukhov_palette(img, palette, &self.reglages)
+    }
+}
+
+/// Diffusion d'erreur de Zhou-Fang vers la palette.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZhouFang {
+    pub reglages: Reglages,
+}
+
+impl Ditherer for ZhouFang {
+    fn nom(&self) -> &str {
+        "zhouFang"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        diffusion_d_erreur_zhou_fang_palette(img, palette, &self.reglages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chaque_ligne_d_ostromoukhov_somme_a_son_diviseur() {
+        for (intensite, [droite, bas_gauche, bas, somme]) in OSTROMOUKHOV.iter().enumerate() {
+            assert!(*droite >= 0 && *bas_gauche >= 0 && *bas >= 0, "intensité {}", intensite);
+            assert_eq!(droite + bas_gauche + bas, *somme, "intensité {}", intensite);
+        }
+    }
+
+    #[test]
+    fn la_table_d_ostromoukhov_suit_la_table_publiee() {
+        let references = [
+            (0, [13, 0, 5, 18]),
+            (11, [501, 224, 211, 936]),
+            (32, [20, 10, 19, 49]),
+            (63, [2447, 2200, 57, 4704]),
+            (64, [11, 10, 0, 21]),
+            (76, [119, 47, 29, 195]),
+            (77, [4, 1, 1, 6]),
+            (85, [4, 1, 1, 6]),
+            (86, [65, 18, 17, 100]),
+            (94, [155, 86, 59, 300]),
+            (95, [5, 3, 2, 10]),
+            (107, [5, 3, 2, 10]),
+            (108, [305, 176, 119, 600]),
+            (126, [395, 104, 101, 600]),
+            (127, [4, 1, 1, 6]),
+        ];
+        for (intensite, ligne) in references {
+            assert_eq!(OSTROMOUKHOV[intensite], ligne, "intensité {}", intensite);
+        }
+    }
+
+    #[test]
+    fn l_interpolation_passe_par_les_niveaux_cles() {
+        let normaliser = |poids: [i32; 3]| {
+            let somme: i32 = poids.iter().sum();
+            poids.map(|p| p as f32 / somme as f32)
+        };
+        let coefficients = interpoler(ZHOU_FANG_COEFFICIENTS, normaliser);
+        for &(cle, poids) in ZHOU_FANG_COEFFICIENTS {
+            assert_eq!(coefficients[cle as usize], normaliser(poids), "niveau {}", cle);
+        }
+        for (n, poids) in coefficients.iter().enumerate() {
+            assert!((poids.iter().sum::<f32>() - 1.0).abs() < 1e-5, "niveau {}", n);
+        }
+
+        let modulation = interpoler(ZHOU_FANG_MODULATION, |force| [force; 3]);
+        for &(cle, force) in ZHOU_FANG_MODULATION {
+            assert_eq!(modulation[cle as usize], [force; 3], "niveau {}", cle);
+        }
+        for paire in ZHOU_FANG_MODULATION.windows(2) {
+            let ((debut, a), (fin, b)) = (paire[0], paire[1]);
+            for n in debut..=fin {
+                let force = modulation[n as usize][0];
+                assert!(force >= a.min(b) && force <= a.max(b), "niveau {}", n);
+            }
+        }
+    }
+
+    #[test]
+    fn les_intensites_sont_ramenees_en_miroir_sur_la_moitie_basse() {
+        for intensite in 0..=255 {
+            let attendu = if intensite <= 127 { intensite } else { 255 - intensite };
+            assert_eq!(niveau(intensite as f32), attendu, "intensité {}", intensite);
+            assert_eq!(niveau(intensite as f32), niveau((255 - intensite) as f32), "intensité {}", intensite);
+        }
+        assert_eq!(niveau(-20.0), 0);
+        assert_eq!(niveau(300.0), 0);
+        assert_eq!(niveau(127.4), 127);
+        assert_eq!(niveau(127.6), 127);
+    }
+}
@@ -0,0 +1,153 @@
+//! Mesures de distance entre couleurs utilisées pour trouver la couleur la plus proche d'une palette.
+
+use image::Rgb;
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+use crate::conversion::{rgb_vers_lab, rgb_vers_oklab};
+
+/// Métrique utilisée pour comparer deux couleurs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Distance {
+    /// Distance euclidienne sur les composantes sRGB brutes.
+    #[default]
+    Euclidienne,
+    /// Distance euclidienne pondérée selon la moyenne du rouge (« redmean »), peu coûteuse.
+    Redmean,
+    /// CIE76 : distance euclidienne dans CIELAB.
+    DeltaE76,
+    /// CIE94 (constantes des arts graphiques).
+    DeltaE94,
+    /// CIEDE2000.
+    Ciede2000,
+    /// Distance euclidienne dans Oklab.
+    Oklab,
+}
+
+impl FromStr for Distance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "euclidean" => Ok(Distance::Euclidienne),
+            "redmean" => Ok(Distance::Redmean),
+            "de76" => Ok(Distance::DeltaE76),
+            "de94" => Ok(Distance::DeltaE94),
+            "de2000" => Ok(Distance::Ciede2000),
+            "oklab" => Ok(Distance::Oklab),
+            _ => Err(format!("Distance invalide: {}", s)),
+        }
+    }
+}
+
+impl Distance {
+    /// Coordonnées de `couleur` dans l'espace où travaille la métrique
+    /// (sRGB, CIELAB ou Oklab), à passer ensuite à [`Distance::ecart`].
+    pub fn coordonnees(&self, couleur: &Rgb<u8>) -> [f32; 3] {
+        match self {
+            Distance::Euclidienne | Distance::Redmean => [couleur[0] as f32, couleur[1] as f32, couleur[2] as f32],
+            Distance::DeltaE76 | Distance::DeltaE94 | Distance::Ciede2000 => rgb_vers_lab(couleur),
+            Distance::Oklab => rgb_vers_oklab(couleur),
+        }
+    }
+
+    /// Écart entre deux couleurs déjà converties par [`Distance::coordonnees`].
+    pub fn ecart(&self, a: &[f32; 3], b: &[f32; 3]) -> f32 {
+        match self {
+            Distance::Euclidienne | Distance::DeltaE76 | Distance::Oklab => {
+                ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+            }
+            Distance::Redmean => {
+                let r_moyen = (a[0] + b[0]) / 2.0;
+                ((2.0 + r_moyen / 256.0) * (a[0] - b[0]).powi(2)
+                    + 4.0 * (a[1] - b[1]).powi(2)
+                    + (2.0 + (255.0 - r_moyen) / 256.0) * (a[2] - b[2]).powi(2))
+                .sqrt()
+            }
+            Distance::DeltaE94 => delta_e94(a, b),
+            Distance::Ciede2000 => ciede2000(a, b),
+        }
+    }
+
+    /// Distance entre deux couleurs sRGB.
+    pub fn distance(&self, a: &Rgb<u8>, b: &Rgb<u8>) -> f32 {
+        self.ecart(&self.coordonnees(a), &self.coordonnees(b))
+    }
+}
+
+fn delta_e94(lab1: &[f32; 3], lab2: &[f32; 3]) -> f32 {
+    let c1 = lab1[1].hypot(lab1[2]);
+    let c2 = lab2[1].hypot(lab2[2]);
+    let delta_l = lab1[0] - lab2[0];
+    let delta_c = c1 - c2;
+    let delta_a = lab1[1] - lab2[1];
+    let delta_b = lab1[2] - lab2[2];
+    let delta_h2 = (delta_a.powi(2) + delta_b.powi(2) - delta_c.powi(2)).max(0.0);
+
+    let s_c = 1.0 + 0.045 * c1;
+    let s_h = 1.0 + 0.015 * c1;
+    (delta_l.powi(2) + (delta_c / s_c).powi(2) + delta_h2 / s_h.powi(2)).sqrt()
+}
+
+fn ciede2000(lab1: &[f32; 3], lab2: &[f32; 3]) -> f32 {
+    let (l1, a1, b1) = (lab1[0], lab1[1], lab1[2]);
+    let (l2, a2, b2) = (lab2[0], lab2[1], lab2[2]);
+
+    let c_moyen = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (c_moyen.powi(7) / (c_moyen.powi(7) + 25f32.powi(7))).sqrt());
+    let a1p = (1.0 + g) * a1;
+    let a2p = (1.0 + g) * a2;
+    let c1p = a1p.hypot(b1);
+    let c2p = a2p.hypot(b2);
+    let angle = |b: f32, a: f32| {
+        if b == 0.0 && a == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let h1p = angle(b1, a1p);
+    let h2p = angle(b2, a2p);
+
+    let delta_lp = l2 - l1;
+    let delta_cp = c2p - c1p;
+    let delta_hp = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p - h1p > 180.0 {
+        h2p - h1p - 360.0
+    } else {
+        h2p - h1p + 360.0
+    };
+    let delta_grand_hp = 2.0 * (c1p * c2p).sqrt() * (delta_hp / 2.0).to_radians().sin();
+
+    let l_moyen = (l1 + l2) / 2.0;
+    let c_moyen_p = (c1p + c2p) / 2.0;
+    let h_moyen_p = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_moyen_p - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_moyen_p).to_radians().cos()
+        + 0.32 * (3.0 * h_moyen_p + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_moyen_p - 63.0).to_radians().cos();
+    let delta_theta = 30.0 * (-((h_moyen_p - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_moyen_p.powi(7) / (c_moyen_p.powi(7) + 25f32.powi(7))).sqrt();
+    let s_l = 1.0 + 0.015 * (l_moyen - 50.0).powi(2) / (20.0 + (l_moyen - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_moyen_p;
+    let s_h = 1.0 + 0.015 * c_moyen_p * t;
+    let r_t = -(2.0 * delta_theta * PI / 180.0).sin() * r_c;
+
+    ((delta_lp / s_l).powi(2)
+        + (delta_cp / s_c).powi(2)
+        + (delta_grand_hp / s_h).powi(2)
+        + r_t * (delta_cp / s_c) * (delta_grand_hp / s_h))
+        .sqrt()
+}
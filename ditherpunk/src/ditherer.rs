@@ -0,0 +1,143 @@
+use image::{Rgb, RgbImage};
+
+use crate::conversion::Luma;
+use crate::dbs::{RechercheBinaireDirecte, ITERATIONS_DBS};
+use crate::diffusion::{
+    DiffusionMatrice, DiffusionSimple, DiffusionSimplePalette, FloydSteinberg,
+};
+use crate::diffusion_variable::{Ostromoukhov, ZhouFang};
+use crate::palette::Palette;
+use crate::riemersma::{Riemersma, RiemersmaPalette};
+use crate::tramage::{
+    Monochrome, PassageAPalette, TramageAleatoire, TramageAleatoirePalette, TramageMotif, TramageOrdonne,
+    TramageOrdonnePalette,
+};
+use crate::yliluoma::{AlgorithmeYliluoma, Yliluoma};
+
+/// Interface commune à tous les algorithmes de tramage.
+///
+/// Les algorithmes monochromes qui ne travaillent qu'en noir et blanc ignorent la palette.
+pub trait Ditherer {
+    /// Nom court de l'algorithme, utilisé pour le retrouver dans un [`Registre`].
+    fn nom(&self) -> &str;
+
+    /// Applique l'algorithme à `img` et renvoie l'image tramée.
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage;
+}
+
+/// Réglages partagés par les algorithmes de tramage.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Reglages {
+    /// Calcule la luminance et diffuse l'erreur en lumière linéaire plutôt que sur
+    /// les valeurs sRGB encodées ; le résultat est réencodé en sRGB.
+    pub lineaire: bool,
+    /// Ramène les valeurs entre 0 et 255 à chaque diffusion d'erreur. Par défaut, l'erreur
+    /// accumulée peut déborder, ce qui reproduit plus fidèlement les tons de l'image.
+    pub ecretage: bool,
+    /// Parcourt les lignes en serpentin (boustrophédon) lors de la diffusion d'erreur :
+    /// une ligne sur deux est lue de droite à gauche avec le noyau retourné, ce qui
+    /// évite les motifs en diagonale.
+    pub serpentin: bool,
+    /// Formule de conversion en niveaux de gris des algorithmes en noir et blanc.
+    pub luma: Luma,
+}
+
+impl Reglages {
+    /// Niveau de gris du pixel sur l'échelle 0..=255, selon `luma`, en lumière linéaire avec `lineaire`.
+    pub fn gris(&self, pixel: &Rgb<u8>) -> f32 {
+        self.luma.gris(pixel, self.lineaire)
+    }
+}
+
+/// Applique plusieurs algorithmes à la suite, la sortie de l'un servant d'entrée au suivant.
+pub struct Chaine {
+    nom: String,
+    etapes: Vec<Box<dyn Ditherer>>,
+}
+
+impl Chaine {
+    pub fn new(etapes: Vec<Box<dyn Ditherer>>) -> Self {
+        let nom = etapes.iter().map(|e| e.nom()).collect::<Vec<_>>().join("+");
+        Chaine { nom, etapes }
+    }
+}
+
+impl Ditherer for Chaine {
+    fn nom(&self) -> &str {
+        &self.nom
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        self.etapes
+            .iter()
+            .fold(img.clone(), |image, etape| etape.dither(&image, palette))
+    }
+}
+
+/// Liste d'algorithmes de tramage accessibles par leur nom.
+#[derive(Default)]
+pub struct Registre {
+    ditherers: Vec<Box<dyn Ditherer>>,
+}
+
+impl Registre {
+    /// Registre vide.
+    pub fn new() -> Self {
+        Registre::default()
+    }
+
+    /// Registre contenant tous les algorithmes fournis par la bibliothèque, avec les réglages par défaut.
+    pub fn par_defaut() -> Self {
+        Registre::avec_reglages(Reglages::default())
+    }
+
+    /// Registre contenant tous les algorithmes fournis par la bibliothèque, configurés avec `reglages`.
+    pub fn avec_reglages(reglages: Reglages) -> Self {
+        let mut registre = Registre::new();
+        registre.enregistrer(Box::new(Monochrome { reglages, ..Monochrome::default() }));
+        registre.enregistrer(Box::new(PassageAPalette));
+        registre.enregistrer(Box::new(TramageAleatoire { reglages, ..TramageAleatoire::default() }));
+        registre.enregistrer(Box::new(TramageAleatoirePalette { reglages, ..TramageAleatoirePalette::default() }));
+        registre.enregistrer(Box::new(TramageOrdonne { reglages, ..TramageOrdonne::default() }));
+        registre.enregistrer(Box::new(TramageOrdonnePalette { reglages, ..TramageOrdonnePalette::default() }));
+        registre.enregistrer(Box::new(TramageMotif { reglages, ..TramageMotif::default() }));
+        for algorithme in [AlgorithmeYliluoma::Un, AlgorithmeYliluoma::Deux, AlgorithmeYliluoma::Trois] {
+            registre.enregistrer(Box::new(Yliluoma { algorithme, reglages, ..Yliluoma::default() }));
+        }
+        registre.enregistrer(Box::new(DiffusionSimple { reglages, ..DiffusionSimple::default() }));
+        registre.enregistrer(Box::new(DiffusionSimplePalette { reglages }));
+        registre.enregistrer(Box::new(FloydSteinberg { reglages }));
+        registre.enregistrer(Box::new(Ostromoukhov { reglages }));
+        registre.enregistrer(Box::new(ZhouFang { reglages }));
+        registre.enregistrer(Box::new(Riemersma { reglages, ..Riemersma::default() }));
+        registre.enregistrer(Box::new(RiemersmaPalette { reglages }));
+        registre.enregistrer(Box::new(RechercheBinaireDirecte {
+            depart: Box::new(FloydSteinberg { reglages }),
+            iterations: ITERATIONS_DBS,
+            reglages,
+        }));
+        for noyau in DiffusionMatrice::catalogue() {
+            registre.enregistrer(Box::new(DiffusionMatrice { reglages, ..noyau }));
+        }
+        registre
+    }
+
+    /// Ajoute un algorithme, en remplaçant celui qui porterait déjà le même nom.
+    pub fn enregistrer(&mut self, ditherer: Box<dyn Ditherer>) {
+        self.ditherers.retain(|d| d.nom() != ditherer.nom());
+        self.ditherers.push(ditherer);
+    }
+
+    pub fn get(&self, nom: &str) -> Option<&dyn Ditherer> {
+        self.ditherers.iter().find(|d| d.nom() == nom).map(|d| d.as_ref())
+    }
+
+    /// Noms des algorithmes enregistrés, dans l'ordre d'enregistrement.
+    pub fn noms(&self) -> impl Iterator<Item = &str> {
+        self.ditherers.iter().map(|d| d.nom())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Ditherer> {
+        self.ditherers.iter().map(|d| d.as_ref())
+    }
+}
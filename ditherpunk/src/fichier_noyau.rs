@@ -0,0 +1,340 @@
+//! Lecture de noyaux de diffusion d'erreur définis par l'utilisateur.
+//!
+//! Trois formats sont acceptés, dans un fichier ou directement en ligne de commande :
+//! - texte : une ligne de poids par ligne du noyau (ou séparées par `;`), `*` marquant le pixel
+//!   courant, et `/ N` donnant le diviseur, par exemple `0 * 7; 3 5 1 / 16`,
+//! - JSON : `{"weights": [[0, 0, 7], [3, 5, 1]], "divisor": 16, "anchor": [1, 0]}`,
+//! - TOML : des lignes `weights = [[0, 0, 7], [3, 5, 1]]`, `divisor = 16`, `anchor = [1, 0]`.
+//!
+//! L'ancre `[colonne, ligne]` donne la position du pixel courant ; la clé `origin` n'en donne
+//! que la colonne, sur la première ligne. Le diviseur vaut par défaut la somme des poids et
+//! l'ancre le milieu de la première ligne.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+use crate::diffusion::DiffusionMatrice;
+use crate::ditherer::Reglages;
+
+/// Imbrication maximale des listes JSON et TOML : les poids sont une liste de lignes.
+const PROFONDEUR_MAX: usize = 2;
+
+/// Extensions des fichiers de noyau, qui distinguent un chemin d'une description en ligne.
+const EXTENSIONS: [&str; 3] = ["txt", "json", "toml"];
+
+/// Erreur survenue lors de la lecture d'un noyau de diffusion.
+#[derive(Debug)]
+pub enum ErreurNoyau {
+    /// Le fichier n'a pas pu être lu.
+    Lecture(std::io::Error),
+    /// La source a l'extension d'un fichier de noyau, mais ce fichier n'existe pas.
+    FichierIntrouvable(String),
+    /// La description est mal formée.
+    Invalide { format: &'static str, message: String },
+}
+
+impl fmt::Display for ErreurNoyau {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurNoyau::Lecture(erreur) => write!(f, "Lecture du fichier de noyau impossible : {}", erreur),
+            ErreurNoyau::FichierIntrouvable(chemin) => write!(f, "Fichier de noyau introuvable : {}", chemin),
+            ErreurNoyau::Invalide { format, message } => write!(f, "Noyau {} invalide : {}", format, message),
+        }
+    }
+}
+
+impl Error for ErreurNoyau {}
+
+impl From<std::io::Error> for ErreurNoyau {
+    fn from(erreur: std::io::Error) -> Self {
+        ErreurNoyau::Lecture(erreur)
+    }
+}
+
+fn invalide(format: &'static str, message: impl Into<String>) -> ErreurNoyau {
+    ErreurNoyau::Invalide { format, message: message.into() }
+}
+
+/// Charge un noyau depuis `source`, qui est soit le chemin d'un fichier, soit la description elle-même.
+///
+/// Le noyau prend le nom du fichier, ou `custom` pour une description en ligne. Une source terminée
+/// par `.txt`, `.json` ou `.toml` qui ne désigne aucun fichier est signalée comme fichier introuvable.
+pub fn charger_noyau(source: &str) -> Result<DiffusionMatrice, ErreurNoyau> {
+    let chemin = Path::new(source);
+    let extension = chemin.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).unwrap_or_default();
+    if chemin.is_file() {
+        let nom = chemin.file_stem().and_then(|n| n.to_str()).unwrap_or("custom");
+        lire_noyau(nom, &fs::read_to_string(chemin)?)
+    } else if EXTENSIONS.contains(&extension.as_str()) {
+        Err(ErreurNoyau::FichierIntrouvable(source.to_string()))
+    } else {
+        lire_noyau("custom", source)
+    }
+}
+
+/// Lit un noyau au format texte, JSON ou TOML, le format étant déduit du contenu.
+pub fn lire_noyau(nom: &str, contenu: &str) -> Result<DiffusionMatrice, ErreurNoyau> {
+    let contenu = contenu.trim();
+    if contenu.starts_with('{') {
+        lire_json(nom, contenu)
+    } else if contenu.lines().any(|ligne| ligne.split('#').next().unwrap_or_default().contains('=')) {
+        lire_toml(nom, contenu)
+    } else {
+        lire_texte(nom, contenu)
+    }
+}
+
+/// Lit un noyau au format texte : des lignes de poids séparés par des espaces ou des virgules.
+pub fn lire_texte(nom: &str, contenu: &str) -> Result<DiffusionMatrice, ErreurNoyau> {
+    let mut lignes = Vec::new();
+    let mut diviseur = None;
+    let mut ancre = None;
+
+    for segment in contenu.lines().flat_map(|ligne| ligne.split('#').next().unwrap_or_default().split(';')) {
+        let (poids, fin) = match segment.split_once('/') {
+            Some((poids, fin)) => (poids, Some(fin)),
+            None => (segment, None),
+        };
+
+        let mut ligne = Vec::new();
+        for valeur in poids.split(|c: char| c.is_whitespace() || c == ',').filter(|v| !v.is_empty()) {
+            if valeur == "*" {
+                if ancre.is_some() {
+                    return Err(invalide("texte", "le pixel courant '*' doit apparaître une seule fois"));
+                }
+                ancre = Some((ligne.len(), lignes.len()));
+                ligne.push(0);
+            } else {
+                ligne.push(valeur.parse().map_err(|_| invalide("texte", format!("poids '{}'", valeur)))?);
+            }
+        }
+        if !ligne.is_empty() {
+            lignes.push(ligne);
+        }
+
+        if let Some(fin) = fin {
+            let fin = fin.trim();
+            diviseur = Some(fin.parse().map_err(|_| invalide("texte", format!("diviseur '{}'", fin)))?);
+        }
+    }
+
+    construire("texte", nom, lignes, diviseur, ancre)
+}
+
+/// Lit un noyau au format JSON : un objet avec les clés `weights`, `divisor` et `anchor` (ou `origin`).
+pub fn lire_json(nom: &str, contenu: &str) -> Result<DiffusionMatrice, ErreurNoyau> {
+    let mut lecteur = Lecteur { format: "JSON", caracteres: contenu.chars().peekable() };
+    lecteur.attendre('{')?;
+    let mut cles = Vec::new();
+    loop {
+        lecteur.sauter_separateurs();
+        if lecteur.caracteres.peek() == Some(&'}') {
+            lecteur.caracteres.next();
+            break;
+        }
+        let cle = lecteur.cle()?;
+        lecteur.attendre(':')?;
+        cles.push((cle, lecteur.valeur(0)?));
+    }
+    lecteur.sauter_separateurs();
+    if let Some(c) = lecteur.caracteres.next() {
+        return Err(invalide("JSON", format!("caractère '{}' après la fin de l'objet", c)));
+    }
+    depuis_cles("JSON", nom, cles)
+}
+
+/// Lit un noyau au format TOML : des lignes `weights = [...]`, `divisor = N` et `anchor = [colonne, ligne]`.
+pub fn lire_toml(nom: &str, contenu: &str) -> Result<DiffusionMatrice, ErreurNoyau> {
+    let mut lecteur = Lecteur { format: "TOML", caracteres: contenu.chars().peekable() };
+    let mut cles = Vec::new();
+    loop {
+        lecteur.sauter_separateurs();
+        if lecteur.caracteres.peek().is_none() {
+            break;
+        }
+        let cle = lecteur.cle()?;
+        lecteur.attendre('=')?;
+        cles.push((cle, lecteur.valeur(0)?));
+    }
+    depuis_cles("TOML", nom, cles)
+}
+
+/// Valeur lue dans une description JSON ou TOML.
+enum Valeur {
+    Entier(i32),
+    Liste(Vec<Valeur>),
+}
+
+/// Analyseur minimal commun aux formats JSON et TOML, limité aux entiers et aux listes.
+struct Lecteur<'a> {
+    format: &'static str,
+    caracteres: Peekable<Chars<'a>>,
+}
+
+impl Lecteur<'_> {
+    /// Saute les blancs, les virgules et les commentaires `#`.
+    fn sauter_separateurs(&mut self) {
+        while let Some(&c) = self.caracteres.peek() {
+            if c == '#' {
+                while self.caracteres.next_if(|&c| c != '\n').is_some() {}
+            } else if c.is_whitespace() || c == ',' {
+                self.caracteres.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn attendre(&mut self, attendu: char) -> Result<(), ErreurNoyau> {
+        self.sauter_separateurs();
+        match self.caracteres.next() {
+            Some(c) if c == attendu => Ok(()),
+            Some(c) => Err(invalide(self.format, format!("'{}' attendu, '{}' trouvé", attendu, c))),
+            None => Err(invalide(self.format, format!("'{}' attendu en fin de texte", attendu))),
+        }
+    }
+
+    /// Lit une clé, entre guillemets ou nue.
+    fn cle(&mut self) -> Result<String, ErreurNoyau> {
+        self.sauter_separateurs();
+        let mut cle = String::new();
+        if self.caracteres.next_if_eq(&'"').is_some() {
+            loop {
+                match self.caracteres.next() {
+                    Some('"') => break,
+                    Some(c) => cle.push(c),
+                    None => return Err(invalide(self.format, "guillemet fermant manquant")),
+                }
+            }
+        } else {
+            while let Some(c) = self.caracteres.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '-') {
+                cle.push(c);
+            }
+        }
+        if cle.is_empty() {
+            return Err(invalide(self.format, "clé attendue"));
+        }
+        Ok(cle)
+    }
+
+    /// Lit un entier ou une liste de valeurs, imbriquée dans `profondeur` listes.
+    fn valeur(&mut self, profondeur: usize) -> Result<Valeur, ErreurNoyau> {
+        self.sauter_separateurs();
+        if self.caracteres.next_if_eq(&'[').is_some() {
+            if profondeur >= PROFONDEUR_MAX {
+                return Err(invalide(self.format, format!("listes imbriquées sur plus de {} niveaux", PROFONDEUR_MAX)));
+            }
+            let mut liste = Vec::new();
+            loop {
+                self.sauter_separateurs();
+                if self.caracteres.next_if_eq(&']').is_some() {
+                    return Ok(Valeur::Liste(liste));
+                }
+                if self.caracteres.peek().is_none() {
+                    return Err(invalide(self.format, "']' manquant"));
+                }
+                liste.push(self.valeur(profondeur + 1)?);
+            }
+        }
+
+        let mut nombre = String::new();
+        while let Some(c) = self.caracteres.next_if(|c| c.is_ascii_digit() || *c == '-' || *c == '+') {
+            nombre.push(c);
+        }
+        nombre
+            .parse()
+            .map(Valeur::Entier)
+            .map_err(|_| invalide(self.format, format!("entier attendu, '{}' trouvé", nombre)))
+    }
+}
+
+/// Assemble un noyau à partir des clés lues en JSON ou en TOML.
+fn depuis_cles(format: &'static str, nom: &str, cles: Vec<(String, Valeur)>) -> Result<DiffusionMatrice, ErreurNoyau> {
+    let mut lignes = None;
+    let mut diviseur = None;
+    let mut ancre = None;
+
+    for (cle, valeur) in cles {
+        match (cle.as_str(), valeur) {
+            ("weights" | "poids", Valeur::Liste(liste)) => {
+                let matrice = liste
+                    .into_iter()
+                    .map(|ligne| match ligne {
+                        Valeur::Liste(ligne) => ligne
+                            .into_iter()
+                            .map(|poids| match poids {
+                                Valeur::Entier(poids) => Ok(poids),
+                                Valeur::Liste(_) => Err(invalide(format, "les poids doivent être des entiers")),
+                            })
+                            .collect(),
+                        Valeur::Entier(_) => Err(invalide(format, "'weights' doit être une liste de lignes")),
+                    })
+                    .collect::<Result<Vec<Vec<i32>>, _>>()?;
+                lignes = Some(matrice);
+            }
+            ("divisor" | "diviseur", Valeur::Entier(valeur)) => diviseur = Some(valeur),
+            ("origin" | "origine", Valeur::Entier(colonne)) => ancre = Some((positif(format, colonne)?, 0)),
+            ("anchor" | "ancre", Valeur::Liste(position)) => match position.as_slice() {
+                [Valeur::Entier(colonne), Valeur::Entier(ligne)] => {
+                    ancre = Some((positif(format, *colonne)?, positif(format, *ligne)?))
+                }
+                _ => return Err(invalide(format, "'anchor' doit être de la forme [colonne, ligne]")),
+            },
+            ("weights" | "poids" | "divisor" | "diviseur" | "origin" | "origine" | "anchor" | "ancre", _) => {
+                return Err(invalide(format, format!("type inattendu pour '{}'", cle)));
+            }
+            _ => return Err(invalide(format, format!("clé inconnue '{}'", cle))),
+        }
+    }
+
+    let lignes = lignes.ok_or_else(|| invalide(format, "clé 'weights' manquante"))?;
+    construire(format, nom, lignes, diviseur, ancre)
+}
+
+fn positif(format: &'static str, valeur: i32) -> Result<usize, ErreurNoyau> {
+    usize::try_from(valeur).map_err(|_| invalide(format, format!("position négative {}", valeur)))
+}
+
+/// Vérifie le noyau et complète le diviseur et l'ancre par leurs valeurs par défaut.
+fn construire(
+    format: &'static str,
+    nom: &str,
+    lignes: Vec<Vec<i32>>,
+    diviseur: Option<i32>,
+    ancre: Option<(usize, usize)>,
+) -> Result<DiffusionMatrice, ErreurNoyau> {
+    let premiere = lignes.first().filter(|ligne| !ligne.is_empty()).ok_or_else(|| invalide(format, "aucun poids"))?;
+    let (colonne, ligne) = ancre.unwrap_or((premiere.len() / 2, 0));
+    if lignes.get(ligne).is_none_or(|poids| colonne >= poids.len()) {
+        return Err(invalide(format, format!("l'ancre ({}, {}) est hors du noyau", colonne, ligne)));
+    }
+    // Les pixels déjà traités (lignes au-dessus, ou à gauche sur la même ligne) ne peuvent plus recevoir d'erreur.
+    let deja_traites = lignes[..ligne].iter().flatten().chain(&lignes[ligne][..=colonne]);
+    if deja_traites.into_iter().any(|&poids| poids != 0) {
+        return Err(invalide(format, "le pixel courant et les poids qui le précèdent doivent être nuls"));
+    }
+
+    let diviseur = match diviseur {
+        Some(diviseur) => diviseur,
+        None => lignes
+            .iter()
+            .flatten()
+            .try_fold(0i32, |somme, &poids| somme.checked_add(poids))
+            .ok_or_else(|| invalide(format, "la somme des poids dépasse les entiers 32 bits"))?,
+    };
+    if diviseur == 0 {
+        return Err(invalide(format, "le diviseur ne peut pas être nul"));
+    }
+
+    Ok(DiffusionMatrice {
+        nom: nom.to_string(),
+        matrice: lignes,
+        facteur: diviseur,
+        ancre: (colonne, ligne),
+        reglages: Reglages::default(),
+    })
+}
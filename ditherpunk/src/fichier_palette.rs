@@ -0,0 +1,306 @@
+//! Lecture des fichiers de palette courants.
+//!
+//! Formats pris en charge :
+//! - GIMP (`.gpl`),
+//! - Adobe Swatch Exchange (`.ase`) et Adobe Color Swatch (`.aco`),
+//! - Paint.NET (`.txt`),
+//! - JASC / Paint Shop Pro (`.pal`),
+//! - listes hexadécimales (`.hex`, une couleur `RRGGBB` par ligne).
+
+use image::Rgb;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::conversion::{cmjn_vers_rgb, lab_vers_rgb, tsv_vers_rgb};
+use crate::couleur::string_to_rgb8;
+use crate::palette::Palette;
+
+/// Erreur survenue lors du chargement d'un fichier de palette.
+#[derive(Debug)]
+pub enum ErreurPalette {
+    /// Le fichier n'a pas pu être lu.
+    Lecture(std::io::Error),
+    /// Ni l'extension ni le contenu ne correspondent à un format connu.
+    FormatInconnu(String),
+    /// Le fichier est mal formé.
+    Invalide { format: &'static str, message: String },
+    /// Le fichier ne contient aucune couleur.
+    Vide,
+}
+
+impl fmt::Display for ErreurPalette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurPalette::Lecture(erreur) => write!(f, "Lecture du fichier de palette impossible : {}", erreur),
+            ErreurPalette::FormatInconnu(chemin) => write!(f, "Format de palette non reconnu : {}", chemin),
+            ErreurPalette::Invalide { format, message } => write!(f, "Palette {} invalide : {}", format, message),
+            ErreurPalette::Vide => write!(f, "Le fichier de palette ne contient aucune couleur"),
+        }
+    }
+}
+
+impl Error for ErreurPalette {}
+
+impl From<std::io::Error> for ErreurPalette {
+    fn from(erreur: std::io::Error) -> Self {
+        ErreurPalette::Lecture(erreur)
+    }
+}
+
+fn invalide(format: &'static str, message: impl Into<String>) -> ErreurPalette {
+    ErreurPalette::Invalide { format, message: message.into() }
+}
+
+/// Charge un fichier de palette, le format étant déduit de son contenu puis de son extension.
+pub fn charger_palette(chemin: &Path) -> Result<Palette, ErreurPalette> {
+    let octets = fs::read(chemin)?;
+    let extension = chemin
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    let couleurs = if octets.starts_with(b"ASEF") {
+        lire_ase(&octets)?
+    } else if extension == "aco" {
+        lire_aco(&octets)?
+    } else {
+        let texte = String::from_utf8_lossy(&octets);
+        let premiere_ligne = texte.lines().next().unwrap_or_default().trim();
+        if premiere_ligne == "GIMP Palette" {
+            lire_gpl(&texte)?
+        } else if premiere_ligne == "JASC-PAL" {
+            lire_jasc(&texte)?
+        } else {
+            match extension.as_str() {
+                "gpl" => lire_gpl(&texte)?,
+                "pal" => lire_jasc(&texte)?,
+                "txt" => lire_paint_net(&texte)?,
+                "hex" => lire_liste_hex(&texte)?,
+                _ => return Err(ErreurPalette::FormatInconnu(chemin.display().to_string())),
+            }
+        }
+    };
+
+    if couleurs.is_empty() {
+        return Err(ErreurPalette::Vide);
+    }
+    Ok(Palette::new(couleurs))
+}
+
+/// Lit une palette GIMP : un en-tête `GIMP Palette`, puis des lignes `R G B [nom]`.
+pub fn lire_gpl(texte: &str) -> Result<Vec<Rgb<u8>>, ErreurPalette> {
+    let mut lignes = texte.lines();
+    if lignes.next().map(str::trim) != Some("GIMP Palette") {
+        return Err(invalide("GIMP", "en-tête 'GIMP Palette' manquant"));
+    }
+
+    let mut couleurs = Vec::new();
+    for ligne in lignes {
+        let ligne = ligne.trim();
+        if ligne.is_empty() || ligne.starts_with('#') || ligne.starts_with("Name:") || ligne.starts_with("Columns:") {
+            continue;
+        }
+        let composantes: Vec<&str> = ligne.split_whitespace().take(3).collect();
+        couleurs.push(lire_composantes(&composantes).ok_or_else(|| invalide("GIMP", format!("ligne '{}'", ligne)))?);
+    }
+    Ok(couleurs)
+}
+
+/// Lit une palette JASC : `JASC-PAL`, la version `0100`, le nombre de couleurs puis des lignes `R G B`.
+pub fn lire_jasc(texte: &str) -> Result<Vec<Rgb<u8>>, ErreurPalette> {
+    let mut lignes = texte.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lignes.next() != Some("JASC-PAL") {
+        return Err(invalide("JASC", "en-tête 'JASC-PAL' manquant"));
+    }
+    lignes.next().ok_or_else(|| invalide("JASC", "version manquante"))?;
+    let nombre: usize = lignes
+        .next()
+        .and_then(|l| l.parse().ok())
+        .ok_or_else(|| invalide("JASC", "nombre de couleurs manquant"))?;
+
+    let couleurs = lignes
+        .take(nombre)
+        .map(|ligne| {
+            let composantes: Vec<&str> = ligne.split_whitespace().collect();
+            lire_composantes(&composantes).ok_or_else(|| invalide("JASC", format!("ligne '{}'", ligne)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if couleurs.len() != nombre {
+        return Err(invalide("JASC", format!("{} couleurs annoncées, {} trouvées", nombre, couleurs.len())));
+    }
+    Ok(couleurs)
+}
+
+/// Lit une palette Paint.NET : des lignes `AARRGGBB`, les commentaires commençant par `;`.
+pub fn lire_paint_net(texte: &str) -> Result<Vec<Rgb<u8>>, ErreurPalette> {
+    texte
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with(';'))
+        .map(|ligne| {
+            // Une ligne non ASCII ne peut pas être découpée octet par octet.
+            let rrggbb = ligne.get(2..).filter(|_| ligne.len() == 8 && ligne.is_ascii());
+            rrggbb
+                .and_then(|rrggbb| string_to_rgb8(&format!("#{}", rrggbb)).ok())
+                .ok_or_else(|| invalide("Paint.NET", format!("ligne '{}'", ligne)))
+        })
+        .collect()
+}
+
+/// Lit une liste de couleurs hexadécimales, une par ligne, avec ou sans `#`.
+pub fn lire_liste_hex(texte: &str) -> Result<Vec<Rgb<u8>>, ErreurPalette> {
+    texte
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with(';') && !l.starts_with("//"))
+        .map(|ligne| {
+            let hex = ligne.trim_start_matches('#');
+            string_to_rgb8(&format!("#{}", hex)).map_err(|_| invalide("hex", format!("ligne '{}'", ligne)))
+        })
+        .collect()
+}
+
+/// Lit une palette Adobe Swatch Exchange (`ASEF`), groupes compris.
+pub fn lire_ase(octets: &[u8]) -> Result<Vec<Rgb<u8>>, ErreurPalette> {
+    let mut lecteur = Lecteur::new(octets, "ASE");
+    if lecteur.octets(4)? != b"ASEF" {
+        return Err(invalide("ASE", "signature 'ASEF' manquante"));
+    }
+    lecteur.u16()?;
+    lecteur.u16()?;
+    let nombre_blocs = lecteur.u32()?;
+
+    let mut couleurs = Vec::new();
+    for _ in 0..nombre_blocs {
+        let type_bloc = lecteur.u16()?;
+        let longueur = lecteur.u32()? as usize;
+        let mut bloc = Lecteur::new(lecteur.octets(longueur)?, "ASE");
+        if type_bloc != 0x0001 {
+            // Début ou fin de groupe : rien à extraire.
+            continue;
+        }
+
+        let longueur_nom = bloc.u16()? as usize;
+        bloc.octets(longueur_nom * 2)?;
+        let modele = bloc.octets(4)?;
+        let couleur = match modele {
+            b"RGB " => {
+                let (r, g, b) = (bloc.f32()?, bloc.f32()?, bloc.f32()?);
+                let composante = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+                Rgb([composante(r), composante(g), composante(b)])
+            }
+            b"CMYK" => cmjn_vers_rgb(bloc.f32()?, bloc.f32()?, bloc.f32()?, bloc.f32()?),
+            b"LAB " => lab_vers_rgb([bloc.f32()? * 100.0, bloc.f32()?, bloc.f32()?]),
+            b"Gray" => {
+                let gris = (bloc.f32()?.clamp(0.0, 1.0) * 255.0).round() as u8;
+                Rgb([gris, gris, gris])
+            }
+            _ => return Err(invalide("ASE", format!("modèle de couleur inconnu {:?}", String::from_utf8_lossy(modele)))),
+        };
+        couleurs.push(couleur);
+    }
+    Ok(couleurs)
+}
+
+/// Lit une palette Adobe Color Swatch (`.aco`), version 1 ou 2.
+///
+/// Un fichier `.aco` commence le plus souvent par une section version 1, suivie d'une section
+/// version 2 reprenant les mêmes couleurs avec leur nom : seule la première section est lue, et
+/// les noms de la version 2 sont ignorés, comme pour les autres formats.
+pub fn lire_aco(octets: &[u8]) -> Result<Vec<Rgb<u8>>, ErreurPalette> {
+    let mut lecteur = Lecteur::new(octets, "ACO");
+    let version = lecteur.u16()?;
+    if version != 1 && version != 2 {
+        return Err(invalide("ACO", format!("version {} inconnue", version)));
+    }
+    let nombre = lecteur.u16()?;
+
+    let mut couleurs = Vec::with_capacity(nombre as usize);
+    for _ in 0..nombre {
+        let espace = lecteur.u16()?;
+        let valeurs = [lecteur.u16()?, lecteur.u16()?, lecteur.u16()?, lecteur.u16()?];
+        if version == 2 {
+            let longueur_nom = lecteur.u32()? as usize;
+            lecteur.octets(longueur_nom * 2)?;
+        }
+
+        let couleur = match espace {
+            0 => Rgb([(valeurs[0] >> 8) as u8, (valeurs[1] >> 8) as u8, (valeurs[2] >> 8) as u8]),
+            1 => tsv_vers_rgb(
+                valeurs[0] as f32 / 65535.0 * 360.0,
+                valeurs[1] as f32 / 65535.0,
+                valeurs[2] as f32 / 65535.0,
+            ),
+            2 => {
+                // En CMJN, 0 correspond à 100 % d'encre.
+                let encre = |v: u16| 1.0 - v as f32 / 65535.0;
+                cmjn_vers_rgb(encre(valeurs[0]), encre(valeurs[1]), encre(valeurs[2]), encre(valeurs[3]))
+            }
+            7 => lab_vers_rgb([
+                valeurs[0] as f32 / 100.0,
+                valeurs[1] as i16 as f32 / 100.0,
+                valeurs[2] as i16 as f32 / 100.0,
+            ]),
+            8 => {
+                let gris = 255 - (valeurs[0].min(10000) as u32 * 255 / 10000) as u8;
+                Rgb([gris, gris, gris])
+            }
+            _ => return Err(invalide("ACO", format!("espace colorimétrique {} non supporté", espace))),
+        };
+        couleurs.push(couleur);
+    }
+    Ok(couleurs)
+}
+
+/// Lit trois composantes décimales entre 0 et 255.
+fn lire_composantes(composantes: &[&str]) -> Option<Rgb<u8>> {
+    if composantes.len() < 3 {
+        return None;
+    }
+    Some(Rgb([
+        composantes[0].parse().ok()?,
+        composantes[1].parse().ok()?,
+        composantes[2].parse().ok()?,
+    ]))
+}
+
+/// Lecture séquentielle de valeurs gros-boutistes dans un fichier binaire.
+struct Lecteur<'a> {
+    octets: &'a [u8],
+    position: usize,
+    format: &'static str,
+}
+
+impl<'a> Lecteur<'a> {
+    fn new(octets: &'a [u8], format: &'static str) -> Self {
+        Lecteur { octets, position: 0, format }
+    }
+
+    fn octets(&mut self, nombre: usize) -> Result<&'a [u8], ErreurPalette> {
+        let fin = self.position + nombre;
+        if fin > self.octets.len() {
+            return Err(invalide(self.format, "fichier tronqué"));
+        }
+        let tranche = &self.octets[self.position..fin];
+        self.position = fin;
+        Ok(tranche)
+    }
+
+    fn u16(&mut self) -> Result<u16, ErreurPalette> {
+        let o = self.octets(2)?;
+        Ok(u16::from_be_bytes([o[0], o[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ErreurPalette> {
+        let o = self.octets(4)?;
+        Ok(u32::from_be_bytes([o[0], o[1], o[2], o[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, ErreurPalette> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+}
@@ -0,0 +1,73 @@
+//! Bibliothèque de tramage (dithering) d'images.
+//!
+//! Tous les algorithmes travaillent sur des images en mémoire : ils prennent
+//! un [`RgbImage`](image::RgbImage) et renvoient une nouvelle image tramée.
+//! La lecture et l'écriture des fichiers sont laissées à l'appelant (voir le
+//! binaire `ditherpunk`).
+//!
+//! Chaque algorithme implémente le trait [`Ditherer`], ce qui permet de les
+//! enregistrer dans un [`Registre`], de les énumérer ou de les enchaîner avec
+//! une [`Chaine`].
+
+pub mod bayer;
+pub mod bruit;
+pub mod conversion;
+pub mod couleur;
+pub mod dbs;
+pub mod diffusion;
+pub mod diffusion_variable;
+pub mod distance;
+pub mod ditherer;
+pub mod fichier_noyau;
+pub mod fichier_palette;
+pub mod niveaux;
+pub mod palette;
+pub mod presets;
+pub mod quantification;
+pub mod riemersma;
+pub mod seuillage;
+pub mod seuils;
+mod tampon;
+pub mod trame;
+pub mod tramage;
+pub mod yliluoma;
+
+pub use bayer::{ErreurBayer, MatriceBayer, ORDRE_MAX_BAYER, TAILLE_MAX_BAYER};
+pub use bruit::Bruit;
+pub use conversion::Luma;
+pub use couleur::{
+    couleur_la_plus_proche, decouper_couleurs, euclidean_distance, rgb8_to_string, string_to_rgb8,
+    ErreurCouleur,
+};
+pub use dbs::{palette_depart, recherche_binaire_directe, PasseDbs, RapportDbs, RechercheBinaireDirecte, ITERATIONS_DBS};
+pub use diffusion::{
+    diffusion_d_erreur_floyd_steinberg_palette, diffusion_d_erreur_palette_matrice,
+    diffusion_d_erreur_simple, diffusion_d_erreur_simple_niveaux, diffusion_d_erreur_simple_palette,
+    DiffusionMatrice, DiffusionSimple, DiffusionSimplePalette, FloydSteinberg, Noyau, ATKINSON, BURKES, FAN,
+    JARVIS_JUDICE_NINKE, SHIAU_FAN, SHIAU_FAN_2, SIERRA_2, SIERRA_3, SIERRA_LITE, STEVENSON_ARCE, STUCKI,
+};
+pub use diffusion_variable::{
+    diffusion_d_erreur_ostromoukhov_palette, diffusion_d_erreur_zhou_fang_palette, Ostromoukhov, ZhouFang,
+    OSTROMOUKHOV, ZHOU_FANG_COEFFICIENTS, ZHOU_FANG_MODULATION,
+};
+pub use distance::Distance;
+pub use ditherer::{Chaine, Ditherer, Reglages, Registre};
+pub use fichier_noyau::{charger_noyau, lire_noyau, ErreurNoyau};
+pub use fichier_palette::{charger_palette, ErreurPalette};
+pub use niveaux::{en_gris, image_grise, ErreurNiveaux, NiveauxGris};
+pub use palette::Palette;
+pub use presets::{noms_presets, preset, Preset, PRESETS};
+pub use quantification::{extraire_palette, Quantificateur};
+pub use riemersma::{
+    tramage_riemersma, tramage_riemersma_niveaux, tramage_riemersma_palette, Riemersma, RiemersmaPalette,
+};
+pub use seuillage::{seuil_automatique, seuillage_par_paire, MethodeSeuil, FENETRE_PAR_DEFAUT};
+pub use seuils::{CarteSeuils, ErreurSeuils, TAILLE_MAX_BRUIT_BLEU};
+pub use tramage::{
+    ecart_par_defaut, monochrome_par_paire, ordered_dithering, ordered_dithering_motif, ordered_dithering_niveaux,
+    ordered_dithering_palette, passage_a_une_palette, tramage_random, tramage_random_niveaux, tramage_random_palette,
+    Monochrome, PassageAPalette, TramageAleatoire, TramageAleatoirePalette, TramageMotif, TramageOrdonne,
+    TramageOrdonnePalette,
+};
+pub use trame::{ErreurTrame, FormeTrame, MatriceTrame, TAILLE_MAX_TRAME};
+pub use yliluoma::{tramage_yliluoma, AlgorithmeYliluoma, Yliluoma, TAILLE_MAX_CANDIDATS};
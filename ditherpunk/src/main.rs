@@ -1,7 +1,14 @@
-use image::{io::Reader as ImageReader, Rgb};
-use rand::Rng;
-use std::error::Error;
 use argh::FromArgs;
+use ditherpunk::{
+    charger_noyau, charger_palette, en_gris, extraire_palette, image_grise, noms_presets, palette_depart, preset,
+    recherche_binaire_directe, rgb8_to_string, seuil_automatique, AlgorithmeYliluoma, Bruit, CarteSeuils,
+    DiffusionMatrice, DiffusionSimple, Distance, FormeTrame, Luma, MatriceBayer, MatriceTrame, MethodeSeuil,
+    Monochrome, NiveauxGris, Palette, Quantificateur, Reglages, Registre, Riemersma, TramageAleatoire,
+    TramageAleatoirePalette, TramageMotif, TramageOrdonne, TramageOrdonnePalette, Yliluoma, FENETRE_PAR_DEFAUT,
+};
+use image::io::Reader as ImageReader;
+use image::RgbImage;
+use std::error::Error;
 use std::path::Path;
 
 
@@ -17,30 +24,155 @@ struct DitherOptions {
     write_to_dir: String,
 
     /// choix du mode de filtre d'image :
-    /// - "mono" utilise un filtre monochrome avec un couple de couleurs,
+    /// - "mono" utilise un filtre monochrome avec un couple de couleurs, selon le seuil "--threshold",
     /// - "pal" utilise une palette précise,
-    /// - "randTram" applique l'algorithme de tramage aléatoire,
-    /// - "ordered" applique l'algorithme du ordered dithering,
+    /// - "randTram" applique l'algorithme de tramage aléatoire, en couleur si une palette est fournie,
+    /// - "ordered" applique l'algorithme du ordered dithering, en couleur si une palette est fournie,
     /// - "error" applique la diffusion d'erreur avec une palette de couleurs,
+    /// - "yliluoma" applique le tramage positionnel de Yliluoma avec une palette de couleurs,
+    /// - "dbs" affine en noir et blanc, par recherche binaire directe, le résultat de l'algorithme "--dbs-start",
     #[argh(option, short = 'm')]
     mode: Mode,
 
-    /// sélection des couleurs utilisé, soit un couple soit une palette : [black, white, red, blue, green, yellow, cyan, magenta, gray]. Pour sélectionner plusieurs couleurs, séparez les couleurs par des virgules
+    /// sélection des couleurs utilisé, soit un couple soit une palette. Chaque couleur s'écrit "#RRGGBB", "#RGB", "rgb(r,g,b)" ou avec un nom CSS/X11 (black, white, red, teal, ...). Pour sélectionner plusieurs couleurs, séparez les couleurs par des virgules
     #[argh(option, short = 'c')]
     colors: Option<String>,
 
-    /// ordre de la matrice Bayer pour l'option "ordered", par défaut 3
+    /// fichier de palette à utiliser (GIMP .gpl, Adobe .ase/.aco, Paint.NET .txt, JASC .pal ou liste .hex). Ses couleurs s'ajoutent à celles de "--colors"
+    #[argh(option)]
+    palette_file: Option<String>,
+
+    /// palette prédéfinie : gameboy, cga0, cga0-high, cga1, cga1-high, ega, c64, pico8, zxspectrum, mac2, web-safe ou grayscale-N (N gris). Ses couleurs s'ajoutent à celles de "--colors"
+    #[argh(option)]
+    preset: Option<String>,
+
+    /// calcule automatiquement une palette de N couleurs à partir de l'image. Ses couleurs s'ajoutent à celles de "--colors"
+    #[argh(option)]
+    auto: Option<usize>,
+
+    /// algorithme de calcul de la palette automatique :
+    /// - "median" découpe médiane (par défaut),
+    /// - "kmeans" k-moyennes dans l'espace CIELAB,
+    /// - "octree" arbre octal,
+    /// - "wu" quantificateur de Wu,
+    #[argh(option, default = "Quantificateur::MedianCut")]
+    quantizer: Quantificateur,
+
+    /// distance utilisée pour choisir la couleur la plus proche de la palette :
+    /// - "euclidean" distance euclidienne en sRGB (par défaut),
+    /// - "redmean" distance sRGB pondérée par la moyenne du rouge,
+    /// - "de76", "de94" ou "de2000" écarts CIELAB ΔE76, ΔE94 et CIEDE2000,
+    /// - "oklab" distance euclidienne dans Oklab,
+    #[argh(option, default = "Distance::Euclidienne")]
+    distance: Distance,
+
+    /// seuil du mode "mono" :
+    /// - un nombre entre 0 et 255 (128 par défaut),
+    /// - "mean", "median", "otsu" ou "kapur" calculent un seuil pour toute l'image,
+    /// - "sauvola", "niblack" ou "bradley" calculent un seuil local sur une fenêtre autour de chaque pixel,
+    #[argh(option, default = "MethodeSeuil::default()")]
+    threshold: MethodeSeuil,
+
+    /// taille de la fenêtre des seuils locaux ("sauvola", "niblack", "bradley"), en pixels, par défaut 15
+    #[argh(option, default = "FENETRE_PAR_DEFAUT")]
+    window: u32,
+
+    /// calcule la luminance et diffuse l'erreur en lumière linéaire (gamma corrigé) au lieu des valeurs sRGB brutes
+    #[argh(switch)]
+    linear: bool,
+
+    /// formule de conversion en niveaux de gris des modes noir et blanc : "rec601", "rec709" (par défaut), "rec2020", "average", "lightness" (TSL), "value" (TSV), "red", "green", "blue" ou "lstar" (CIE L*)
+    #[argh(option, default = "Luma::Rec709")]
+    luma: Luma,
+
+    /// trame en N niveaux de gris au lieu du noir et blanc ou d'une palette, avec tous les algorithmes sauf "mono" et "dbs" : un nombre de gris régulièrement espacés ("4", "16") ou une liste de gris entre 0 et 255 ("0,96,176,255"). L'image est alors écrite en PNG 8 bits en niveaux de gris
+    #[argh(option)]
+    levels: Option<NiveauxGris>,
+
+    /// ramène les valeurs entre 0 et 255 à chaque étape de la diffusion d'erreur au lieu de laisser l'erreur déborder
+    #[argh(switch)]
+    clamp_error: bool,
+
+    /// parcourt les lignes en serpentin lors de la diffusion d'erreur (une ligne sur deux de droite à gauche)
+    #[argh(switch)]
+    serpentine: bool,
+
+    /// ordre de la matrice Bayer pour l'option "ordered" (matrice 2^ordre × 2^ordre, ordre 8 au plus), par défaut 3. Pour les autres tailles, voir "--bayer-size"
     #[argh(option, short = 'o', default = "3")]
     order: u32,
+
+    /// taille de la matrice Bayer pour l'option "ordered", à la place de "--order" : toute taille entre 1 et 256 (3, 5, 6, 7, 10, ...), les tailles 2^k et 3·2^k donnant les matrices de Bayer classiques
+    #[argh(option)]
+    bayer_size: Option<usize>,
+
+    /// remplace la matrice Bayer par une tuile de bruit bleu N×N générée par void-and-cluster (N entre 1 et 128, 64 conseillé)
+    #[argh(option)]
+    blue_noise: Option<usize>,
+
+    /// remplace la matrice Bayer par une carte de seuils lue dans une image en niveaux de gris (PNG par exemple), répétée en mosaïque
+    #[argh(option)]
+    threshold_map: Option<String>,
+
+    /// remplace la matrice Bayer par une trame à points groupés pour l'impression :
+    /// - "spiral" point qui grossit en spirale,
+    /// - "diagonal" point en losange,
+    /// - "dot45" points ronds sur une grille tournée de 45°,
+    /// - "lines" lignes horizontales,
+    #[argh(option)]
+    screen: Option<FormeTrame>,
+
+    /// taille de la cellule de la trame "--screen", en pixels (256 au plus), par défaut 8
+    #[argh(option, default = "8")]
+    screen_size: usize,
+
+    /// graine des générateurs pseudo-aléatoires (tramage aléatoire, bruit bleu), pour reproduire un résultat. Sans graine, le tramage aléatoire change à chaque exécution et le bruit bleu utilise la graine 0
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// bruit du mode "randTram" :
+    /// - "uniform" bruit blanc uniforme (par défaut),
+    /// - "triangular" bruit à densité triangulaire,
+    /// - "gaussian" bruit gaussien,
+    /// - "ign" bruit à gradient entrelacé (interleaved gradient noise),
+    #[argh(option, default = "Bruit::Uniforme")]
+    noise: Bruit,
+
+    /// amplitude du décalage des tramages ordonné et aléatoire en couleur, sur l'échelle 0..255, par défaut 255 / ∛(nombre de couleurs)
+    #[argh(option)]
+    spread: Option<f32>,
+
+    /// tramage ordonné par motif (algorithme de Knoll) : chaque pixel choisit, selon la matrice de Bayer, une couleur parmi un mélange de couleurs de la palette
+    #[argh(switch)]
+    pattern: bool,
+
+    /// algorithme du mode "yliluoma" : 1 (mélange de deux couleurs, par défaut), 2 (mélange de plusieurs couleurs) ou 3 (deux couleurs ou damier de trois couleurs)
+    #[argh(option, default = "AlgorithmeYliluoma::Un")]
+    yliluoma: AlgorithmeYliluoma,
     
+    /// algorithme de départ du mode "dbs", appliqué en noir et blanc : "randTram", "ordered", "floyd" (par défaut), "jjn"...
+    #[argh(option, default = "String::from(\"floyd\")")]
+    dbs_start: String,
+
+    /// nombre maximal de passes du mode "dbs", par défaut 10
+    #[argh(option, default = "10")]
+    iterations: usize,
+
     /// choix de la diffusion d'erreur :
     /// - "simple" applique la diffusion d'erreur simple,
     /// - "simplePal" applique la diffusion d'erreur simple avec une palette de couleurs,
     /// - "floyd" applique la diffusion d'erreur de Floyd-Steinberg,
     /// - "jjn" applique la diffusion d'erreur de Jarvis-Judice-Ninke,
     /// - "atkinson" applique la diffusion d'erreur d'Atkinson,
+    /// - "stucki", "burkes", "sierra3", "sierra2", "sierraLite", "fan", "shiauFan", "shiauFan2"
+    ///   et "stevensonArce" appliquent les noyaux de diffusion du même nom,
+    /// - "ostromoukhov" et "zhouFang" appliquent les diffusions à coefficients variables d'Ostromoukhov et de Zhou-Fang,
+    /// - "riemersma" applique le tramage de Riemersma le long d'une courbe de Hilbert, "riemersmaPal" avec une palette de couleurs,
     #[argh(option, short = 'e', default = "String::from(\"simple\")")]
     error: String,
+
+    /// noyau de diffusion personnalisé pour le mode "error", qui remplace "-e" : un fichier ou une description en ligne, en texte ("0 * 7; 3 5 1 / 16", "*" marquant le pixel courant), JSON ({"weights": [[0, 0, 7], [3, 5, 1]], "divisor": 16, "anchor": [1, 0]}) ou TOML (weights = ..., divisor = ..., anchor = [colonne, ligne])
+    #[argh(option)]
+    kernel: Option<String>,
 }
 
 /// Enumération des modes disponibles
@@ -51,6 +183,8 @@ enum Mode {
     RandTram,
     Ordered,
     Error,
+    Yliluoma,
+    Dbs,
 }
 
 impl std::str::FromStr for Mode {
@@ -63,115 +197,13 @@ impl std::str::FromStr for Mode {
             "randTram" => Ok(Mode::RandTram),
             "ordered" => Ok(Mode::Ordered),
             "error" => Ok(Mode::Error),
+            "yliluoma" => Ok(Mode::Yliluoma),
+            "dbs" => Ok(Mode::Dbs),
             _ => Err(format!("Mode invalide: {}", s)),
         }
     }
 }
 
-#[derive(Debug)]
-/// Représente une matrice de Bayer, avec les fonctions pour la générer
-struct MatriceBayer {
-    taille: usize,
-    matrice: Vec<Vec<u32>>,
-}
-
-impl MatriceBayer {
-    /// Génère une matrice de Bayer d'ordre `n`
-    fn new_bayer_matrix(ordre: u32) -> Self {
-        let taille = 2usize.pow(ordre);
-        let mut matrice = vec![vec![0; taille]; taille];
-        MatriceBayer::calculer_bayer(&mut matrice, 0, 0, taille, 1, 0);
-        MatriceBayer { taille, matrice }
-    }
-
-    /// Génération récursive de la matrice
-    fn calculer_bayer(matrice: &mut Vec<Vec<u32>>, x: usize, y: usize, taille: usize, etape: u32, valeur: u32) {
-        if taille == 0 {
-            matrice[y][x] = 0;
-            return;
-        }
-
-        //println!("{:?}", valeur);
-
-        if taille == 1 {
-            matrice[y][x] = valeur;
-            return;
-        }
-
-        //let matrice_clone = matrice.clone();
-        // for row in matrice_clone {
-        //     println!("{:?}", row);
-        // }
-
-        let milieu = taille / 2;
-
-        //Calcule haut gauche
-        Self::calculer_bayer(matrice, x, y, milieu, etape*4, valeur+(etape*0));
-        //Calcule bas droite
-        Self::calculer_bayer(matrice, x+milieu, y+milieu, milieu, etape*4, valeur+(etape*1));
-        //Calcule haut droit
-        Self::calculer_bayer(matrice, x+milieu, y, milieu, etape *4, valeur+(etape*2));
-        //Calcule bas gauche
-        Self::calculer_bayer(matrice, x, y+milieu, milieu, etape *4, valeur+(etape*3));
-
-    }
-}
-
-fn rgb8_to_string(composantes: Rgb<u8>) -> String {
-    match composantes {
-        Rgb([255, 0, 0]) => "red".to_string(),
-        Rgb([0, 255, 0]) => "green".to_string(),
-        Rgb([0, 0, 255]) => "blue".to_string(),
-        Rgb([255, 255, 0]) => "yellow".to_string(),
-        Rgb([0, 255, 255]) => "cyan".to_string(),
-        Rgb([255, 0, 255]) => "magenta".to_string(),
-        Rgb([0, 0, 0]) => "black".to_string(),
-        Rgb([255, 255, 255]) => "white".to_string(),
-        Rgb([160, 82, 45]) => "sienna".to_string(),
-        Rgb([128, 0, 128]) => "purple".to_string(),
-        _ => panic!("Couleur non supportée"),
-    }
-}
-
-fn string_to_rgb8(couleur: &str) -> Rgb<u8> {
-    match couleur {
-        "red" => Rgb([255, 0, 0]),
-        "green" => Rgb([0, 255, 0]),
-        "blue" => Rgb([0, 0, 255]),
-        "yellow" => Rgb([255, 255, 0]),
-        "cyan" => Rgb([0, 255, 255]),
-        "magenta" => Rgb([255, 0, 255]),
-        "black" => Rgb([0, 0, 0]),
-        "white" => Rgb([255, 255, 255]),
-        "sienna" => Rgb([160, 82, 45]),
-        "purple" => Rgb([128, 0, 128]),
-        _ => panic!("Couleur non supportée"),
-    }
-}
-
-fn euclidean_distance(color1: &Rgb<u8>, color2: &Rgb<u8>) -> f32 {
-    let r1 = color1[0] as f32;
-    let r2 = color2[0] as f32;
-    let g1 = color1[1] as f32;
-    let g2 = color2[1] as f32;
-    let b1 = color1[2] as f32;
-    let b2 = color2[2] as f32;
-    ((r2 - r1).powf(2.0) + (g2 - g1).powf(2.0) + (b2 - b1).powf(2.0)).sqrt()
-}
-
-fn couleur_la_plus_proche(pixel: &Rgb<u8>, palette: &Vec<Rgb<u8>>) -> Rgb<u8> {
-    let mut min_d = std::f32::MAX;
-    let mut min_couleur = Rgb([0, 0, 0]);
-    for couleur in palette {
-        let d = euclidean_distance(pixel, couleur);
-        if d < min_d {
-            min_d = d;
-            min_couleur = *couleur;
-        }
-    }
-    min_couleur
-}
-
 /// Vérifie et formate un chemin de dossier pour s'assurer qu'il termine par un seul "/".
 fn format_dossier(chemin_dossier: &str) -> String {
     let chemin = Path::new(chemin_dossier);
@@ -184,376 +216,270 @@ fn format_dossier(chemin_dossier: &str) -> String {
     }
 }
 
-fn monochrome_par_paire(chemin_img: &str, paire: Vec<&str>, chemin_dossier: &str) -> Result<(), Box<dyn Error>> {
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-    let couleur1 = string_to_rgb8(paire[0]);
-    let couleur2 = string_to_rgb8(paire[1]);
-    for pixel in img.pixels_mut() {
-        let luma = 0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32;
-        if luma > 128.0 {
-            *pixel = couleur1;
-        } else {
-            *pixel = couleur2;
-        }
-    }
-
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    let output_path = format!(
-        "{}iut_monochrome_{}_{}.jpg",
-        chemin_dossier_formatte,
-        rgb8_to_string(couleur1),
-        rgb8_to_string(couleur2)
-    );
-    img.save(output_path)?;
-    Ok(())
-}
-
-fn passage_a_une_palette(chemin_img: &str, palette: Vec<&str>, chemin_dossier: &str) -> Result<(), Box<dyn Error>> {
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-    for pixel in img.pixels_mut() {
-        let couleur_la_plus_proche =
-            couleur_la_plus_proche(&pixel, &palette.iter().map(|c| string_to_rgb8(c)).collect());
-        *pixel = couleur_la_plus_proche;
-    }
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    let output_path = format!("{}iut_palette_{}.jpg", chemin_dossier_formatte, palette.join("_"));
-    img.save(output_path)?;
-    Ok(())
-}
-
-fn tramage_random(
-    chemin_img: &str,
-    chemin_dossier: &str
-) -> Result<(), Box<dyn Error>> {
-    let mut rng = rand::thread_rng();
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-    for (_x, _y, pixel) in img.enumerate_pixels_mut() {
-        let luma = (0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32)
-            as f64
-            / 255.0;
-        if luma > rng.gen() {
-            *pixel = Rgb([255, 255, 255]);
-        } else {
-            *pixel = Rgb([0, 0, 0]);
-        }
-    }
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    img.save(format!("{}iut_tramage_random.jpg", chemin_dossier_formatte))?;
-    Ok(())
+/// Ouvre l'image `chemin_img` et la convertit en RGB 8 bits.
+fn lire_image(chemin_img: &str) -> Result<RgbImage, Box<dyn Error>> {
+    Ok(ImageReader::open(chemin_img)?.decode()?.to_rgb8())
 }
 
-fn ordered_dithering(
-    chemin_img: &str,
-    ordre: u32,
-    chemin_dossier: &str
-) -> Result<(), Box<dyn Error>> {
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-
-    let bayer = MatriceBayer::new_bayer_matrix(ordre);
-
-    for (_x, _y, pixel) in img.enumerate_pixels_mut() {
-        let luma = (0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32) as f64 / 255.0;
-        let seuil = bayer.matrice[(_y % bayer.taille as u32) as usize][(_x % bayer.taille as u32) as usize] as f32 * 1.0 / (bayer.taille * bayer.taille) as f32;
-        if luma > seuil.into() {
-            *pixel = Rgb([255, 255, 255]);
-        } else {
-            *pixel = Rgb([0, 0, 0]);
-        }
-    }
-
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    img.save(format!("{}iut_ordered_dithering.jpg", chemin_dossier_formatte))?;
-    Ok(())
+/// Construit la partie du nom de fichier décrivant la palette, par exemple `red_blue_1a2b3c`.
+fn suffixe_palette(palette: &Palette) -> String {
+    palette
+        .couleurs()
+        .iter()
+        .map(|&couleur| rgb8_to_string(couleur).trim_start_matches('#').to_string())
+        .collect::<Vec<_>>()
+        .join("_")
 }
 
-fn diffusion_d_erreur_simple(chemin_img: &str, chemin_dossier: &str) -> Result<(), Box<dyn Error>> {
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-    let largeur = img.width();
-    let hauteur = img.height();
-
-    for x in 0..largeur {
-        for y in 0..hauteur {
-            let pixel = img.get_pixel(x, y);
-            let luma =
-                0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32;
-            let nouvelle_valeur = if luma > 128.0 { 255.0 } else { 0.0 };
-            let erreur = luma - nouvelle_valeur;
-
-            img.put_pixel(
-                x,
-                y,
-                Rgb([
-                    nouvelle_valeur as u8,
-                    nouvelle_valeur as u8,
-                    nouvelle_valeur as u8,
-                ]),
-            );
-
-            if x + 1 < largeur {
-                let voisin = img.get_pixel(x + 1, y);
-                let voisin_luma = 0.2126 * voisin[0] as f32
-                    + 0.7152 * voisin[1] as f32
-                    + 0.0722 * voisin[2] as f32;
-                let valeur_mise_a_jour = voisin_luma + 0.5 * erreur;
-                img.put_pixel(
-                    x + 1,
-                    y,
-                    Rgb([
-                        (valeur_mise_a_jour.clamp(0.0, 255.0)) as u8,
-                        (valeur_mise_a_jour.clamp(0.0, 255.0)) as u8,
-                        (valeur_mise_a_jour.clamp(0.0, 255.0)) as u8,
-                    ]),
-                );
-            }
-            if y + 1 < hauteur {
-                let voisin = img.get_pixel(x, y + 1);
-                let voisin_luma = 0.2126 * voisin[0] as f32
-                    + 0.7152 * voisin[1] as f32
-                    + 0.0722 * voisin[2] as f32;
-                let valeur_mise_a_jour = voisin_luma + 0.5 * erreur;
-                img.put_pixel(
-                    x,
-                    y + 1,
-                    Rgb([
-                        (valeur_mise_a_jour.clamp(0.0, 255.0)) as u8,
-                        (valeur_mise_a_jour.clamp(0.0, 255.0)) as u8,
-                        (valeur_mise_a_jour.clamp(0.0, 255.0)) as u8,
-                    ]),
-                );
-            }
-        }
+fn ajouter_au_suffixe(suffixe: &mut String, nom: &str) {
+    if !suffixe.is_empty() {
+        suffixe.push('_');
     }
-
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    img.save(format!("{}iut_diffusion_d_erreur_simple.jpg", chemin_dossier_formatte))?;
-    Ok(())
+    suffixe.push_str(nom);
 }
 
-fn diffusion_d_erreur_simple_palette(
-    chemin_img: &str,
-    palette: &Vec<&str>,
-    chemin_dossier: &str
-) -> Result<(), Box<dyn Error>> {
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-    let largeur = img.width();
-    let hauteur = img.height();
-
-    let palette_rgb: Vec<Rgb<u8>> = palette.iter().map(|&c| string_to_rgb8(c)).collect();
-
-    for y in 0..hauteur {
-        for x in 0..largeur {
-            let current_pixel = img.get_pixel(x, y);
-            let closest = couleur_la_plus_proche(current_pixel, &palette_rgb);
-
-            let error = [
-                current_pixel[0] as i16 - closest[0] as i16,
-                current_pixel[1] as i16 - closest[1] as i16,
-                current_pixel[2] as i16 - closest[2] as i16,
-            ];
-
-            img.put_pixel(x, y, closest);
-
-            if x + 1 < largeur {
-                for c in 0..3 {
-                    let neighbor = img.get_pixel_mut(x + 1, y);
-                    let value = neighbor[c] as i16 + (error[c] as f32 * 0.5) as i16;
-                    neighbor[c] = value.clamp(0, 255) as u8;
-                }
-            }
-            if y + 1 < hauteur {
-                for c in 0..3 {
-                    let neighbor = img.get_pixel_mut(x, y + 1);
-                    let value = neighbor[c] as i16 + (error[c] as f32 * 0.5) as i16;
-                    neighbor[c] = value.clamp(0, 255) as u8;
-                }
-            }
-        }
-    }
-
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    let output_path = format!("{}iut_diffusion_d_erreur_simple_palette_{}.jpg", chemin_dossier_formatte, palette.join("_"));
-
-    img.save(output_path)?;
-    Ok(())
-}
-
-fn diffusion_d_erreur_floyd_steinberg_palette(
-    chemin_img: &str,
-    palette: &Vec<&str>,
-    chemin_dossier: &str
-) -> Result<(), Box<dyn Error>> {
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-    let largeur = img.width();
-    let hauteur = img.height();
-
-    let palette_rgb: Vec<Rgb<u8>> = palette.iter().map(|&c| string_to_rgb8(c)).collect();
-
-    for y in 0..hauteur {
-        for x in 0..largeur {
-            let current_pixel = img.get_pixel(x, y);
-            let closest = couleur_la_plus_proche(current_pixel, &palette_rgb);
-
-            let error = [
-                current_pixel[0] as i16 - closest[0] as i16,
-                current_pixel[1] as i16 - closest[1] as i16,
-                current_pixel[2] as i16 - closest[2] as i16,
-            ];
-
-            img.put_pixel(x, y, closest);
-
-            if x + 1 < largeur {
-                for c in 0..3 {
-                    let neighbor = img.get_pixel_mut(x + 1, y);
-                    let value = neighbor[c] as i16 + (error[c] * 7 / 16);
-                    neighbor[c] = value.clamp(0, 255) as u8;
-                }
-            }
-            if y + 1 < hauteur {
-                if x > 0 {
-                    for c in 0..3 {
-                        let neighbor = img.get_pixel_mut(x - 1, y + 1);
-                        let value = neighbor[c] as i16 + (error[c] * 3 / 16);
-                        neighbor[c] = value.clamp(0, 255) as u8;
-                    }
-                }
-                for c in 0..3 {
-                    let neighbor = img.get_pixel_mut(x, y + 1);
-                    let value = neighbor[c] as i16 + (error[c] * 5 / 16);
-                    neighbor[c] = value.clamp(0, 255) as u8;
-                }
-                if x + 1 < largeur {
-                    for c in 0..3 {
-                        let neighbor = img.get_pixel_mut(x + 1, y + 1);
-                        let value = neighbor[c] as i16 + (error[c] * 1 / 16);
-                        neighbor[c] = value.clamp(0, 255) as u8;
-                    }
-                }
-            }
-        }
-    }
-
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    let output_path = format!("{}iut_diffusion_d_erreur_floyd_steinberg_palette_{}.jpg", chemin_dossier_formatte, palette.join("_"));
-
-    img.save(output_path)?;
-    Ok(())
-}
-
-fn diffusion_d_erreur_palette_matrice(
-    chemin_img: &str,
-    palette: &Vec<&str>,
-    diffusion_matrix: &[&[i32]],
-    factor: i32,
-    chemin_dossier: &str
-) -> Result<(), Box<dyn Error>> {
-    let mut img = ImageReader::open(chemin_img)?.decode()?.to_rgb8();
-    let (width, height) = img.dimensions();
-    let palette_rgb: Vec<Rgb<u8>> = palette.iter().map(|&c| string_to_rgb8(c)).collect();
-
-    for y in 0..height as usize {
-        for x in 0..width as usize {
-            let old_pixel = img.get_pixel(x as u32, y as u32);
-            let old_pixel = Rgb([old_pixel[0] as u8, old_pixel[1] as u8, old_pixel[2] as u8]);
-
-            let new_pixel = couleur_la_plus_proche(&old_pixel, &palette_rgb);
-            img.put_pixel(x as u32, y as u32, new_pixel);
-
-            let error = [
-                old_pixel[0] as f32 - new_pixel[0] as f32,
-                old_pixel[1] as f32 - new_pixel[1] as f32,
-                old_pixel[2] as f32 - new_pixel[2] as f32,
-            ];
-
-            for (dy, row) in diffusion_matrix.iter().enumerate() {
-                for (dx, weight) in row.iter().enumerate() {
-                    let nx = x as i32 + dx as i32 - (row.len() / 2) as i32;
-                    let ny = y as i32 + dy as i32;
-
-                    if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
-                        let neighbor = img.get_pixel(nx as u32, ny as u32);
-                        let mut neighbor_pixel =
-                            [neighbor[0] as f32, neighbor[1] as f32, neighbor[2] as f32];
-
-                        for i in 0..3 {
-                            neighbor_pixel[i] += error[i] * (*weight as f32 / factor as f32);
-                            neighbor_pixel[i] = neighbor_pixel[i].clamp(0.0, 255.0);
-                        }
-
-                        img.put_pixel(
-                            nx as u32,
-                            ny as u32,
-                            Rgb([
-                                neighbor_pixel[0] as u8,
-                                neighbor_pixel[1] as u8,
-                                neighbor_pixel[2] as u8,
-                            ]),
-                        );
-                    }
-                }
-            }
-        }
-    }
-
-    let chemin_dossier_formatte = format_dossier(chemin_dossier);
-    let output_path = format!("{}iut_diffusion_d_erreur_palette_matrice_{}.jpg", chemin_dossier_formatte, palette.join("_"));
-
-    img.save(output_path)?;
-    Ok(())
-}
-
-
 fn main() -> Result<(), Box<dyn Error>> {
     let options: DitherOptions = argh::from_env();
 
     let chemin_img = options.read_image;
     let dossier_ecriture = format_dossier(&options.write_to_dir); // Formatage du chemin de dossier
     let mode = options.mode;
-    let couleurs = options.colors.unwrap_or_else(|| String::from(""));
-    let ordre = options.order;
-    let erreur_diffuse = options.error.as_str();
+    let couleurs = options.colors.unwrap_or_default();
+    let (seuils, nom_seuils) = match (&options.threshold_map, options.blue_noise, options.screen) {
+        (Some(fichier), None, None) => {
+            let chemin = Path::new(fichier);
+            let nom = chemin.file_stem().and_then(|n| n.to_str()).unwrap_or("fichier");
+            (CarteSeuils::charger(chemin)?, Some(format!("seuils_{}", nom)))
+        }
+        (None, Some(taille), None) => {
+            (CarteSeuils::bruit_bleu(taille, options.seed.unwrap_or(0))?, Some(format!("bruit_bleu{}", taille)))
+        }
+        (None, None, Some(forme)) => {
+            let nom = match forme {
+                FormeTrame::Spirale => "spirale",
+                FormeTrame::Diagonale => "diagonale",
+                FormeTrame::Point45 => "point45",
+                FormeTrame::Lignes => "lignes",
+            };
+            let trame = MatriceTrame::new(forme, options.screen_size)?;
+            (trame.into(), Some(format!("trame_{}_{}", nom, options.screen_size)))
+        }
+        (None, None, None) => match options.bayer_size {
+            Some(taille) => (MatriceBayer::avec_taille(taille)?.into(), None),
+            None => (MatriceBayer::new_bayer_matrix(options.order)?.into(), None),
+        },
+        _ => return Err("Les options '--threshold-map', '--blue-noise' et '--screen' s'excluent".into()),
+    };
+    let avec_seuils = |prefixe: &str| match &nom_seuils {
+        Some(nom) => format!("{}_{}", prefixe, nom),
+        None => prefixe.to_string(),
+    };
+    let noyau = options.kernel.as_deref().map(charger_noyau).transpose()?;
+    let erreur_diffuse = noyau.as_ref().map_or(options.error.as_str(), |noyau| noyau.nom.as_str());
+
+    let img = lire_image(&chemin_img)?;
+
+    let mut palette: Palette = couleurs.parse()?;
+    let mut suffixe = suffixe_palette(&palette);
+    if let Some(nom) = &options.preset {
+        let couleurs = preset(nom).ok_or_else(|| {
+            format!("Palette prédéfinie inconnue : {} (disponibles : {})", nom, noms_presets().join(", "))
+        })?;
+        palette.extend(couleurs.couleurs().iter().copied());
+        ajouter_au_suffixe(&mut suffixe, nom);
+    }
+    if let Some(nombre) = options.auto {
+        palette.extend(extraire_palette(&img, nombre, options.quantizer).couleurs().iter().copied());
+        ajouter_au_suffixe(&mut suffixe, &format!("auto{}", nombre));
+    }
+    if let Some(fichier) = &options.palette_file {
+        let chemin = Path::new(fichier);
+        palette.extend(charger_palette(chemin)?.couleurs().iter().copied());
+        ajouter_au_suffixe(&mut suffixe, chemin.file_stem().and_then(|n| n.to_str()).unwrap_or("fichier"));
+    }
 
-    let jarvis_judice_ninke: &[&[i32]] = &[&[0, 0, 0, 7, 5], &[3, 5, 7, 5, 3], &[1, 3, 5, 3, 1]];
-    let atkinson: &[&[i32]] = &[&[0, 0, 1, 1], &[1, 1, 1, 0], &[0, 1, 0, 0]];
+    let palette = match &options.levels {
+        Some(_) if !palette.is_empty() => return Err("L'option '--levels' remplace les couleurs de la palette".into()),
+        Some(niveaux) => {
+            suffixe = if niveaux.sont_uniformes() {
+                format!("gris{}", niveaux.len())
+            } else {
+                format!("gris_{}", niveaux.niveaux().iter().map(u8::to_string).collect::<Vec<_>>().join("_"))
+            };
+            niveaux.palette().avec_distance(options.distance)
+        }
+        None => palette.avec_distance(options.distance),
+    };
+    let gris = options.levels.is_some();
 
-    match mode {
+    let (nom_ditherer, nom_fichier) = match mode {
         Mode::Mono => {
-            let paire: Vec<&str> = couleurs.split(',').collect();
-            if paire.len() != 2 {
+            if gris {
+                return Err("Le mode 'mono' utilise une paire de couleurs, l'option '--levels' ne s'y applique pas".into());
+            }
+            if palette.len() != 2 {
                 return Err("Pour le mode 'mono', fournissez une paire de couleurs (ex: 'white,black')".into());
             }
-            monochrome_par_paire(&chemin_img, paire, &dossier_ecriture)?;
+            let methode = match options.threshold {
+                MethodeSeuil::Fixe(128.0) => String::new(),
+                MethodeSeuil::Fixe(seuil) => format!("seuil{}_", seuil),
+                MethodeSeuil::Moyenne => String::from("moyenne_"),
+                MethodeSeuil::Mediane => String::from("mediane_"),
+                MethodeSeuil::Otsu => String::from("otsu_"),
+                MethodeSeuil::Kapur => String::from("kapur_"),
+                MethodeSeuil::Sauvola => format!("sauvola{}_", options.window),
+                MethodeSeuil::Niblack => format!("niblack{}_", options.window),
+                MethodeSeuil::Bradley => format!("bradley{}_", options.window),
+            };
+            ("mono", format!("iut_monochrome_{}{}", methode, suffixe))
         }
         Mode::Pal => {
-            let palette: Vec<&str> = couleurs.split(',').collect();
-            if palette.iter().all(|&color| color.is_empty()) {
+            if palette.is_empty() {
                 return Err("Pour le mode 'pal', fournissez une palette de couleurs (ex: 'cyan,green,yellow')".into());
             }
-            passage_a_une_palette(&chemin_img, palette, &dossier_ecriture)?;
+            ("pal", format!("iut_palette_{}", suffixe))
         }
         Mode::RandTram => {
-            tramage_random(&chemin_img, &dossier_ecriture)?;
+            let prefixe = match options.noise {
+                Bruit::Uniforme => String::from("iut_tramage_random"),
+                Bruit::Triangulaire => String::from("iut_tramage_random_triangulaire"),
+                Bruit::Gaussien => String::from("iut_tramage_random_gaussien"),
+                Bruit::GradientEntrelace => String::from("iut_tramage_random_ign"),
+            };
+            if gris {
+                ("randTram", format!("{}_{}", prefixe, suffixe))
+            } else if palette.is_empty() {
+                ("randTram", prefixe)
+            } else {
+                ("randTramPal", format!("{}_palette_{}", prefixe, suffixe))
+            }
         }
         Mode::Ordered => {
-            ordered_dithering(&chemin_img, ordre, &dossier_ecriture)?;
+            if options.pattern {
+                if palette.is_empty() {
+                    return Err("Pour l'option '--pattern', fournissez une palette de couleurs (ex: 'cyan,green,yellow')".into());
+                }
+                ("pattern", format!("{}_{}", avec_seuils("iut_ordered_dithering_motif"), suffixe))
+            } else if gris {
+                ("ordered", format!("{}_{}", avec_seuils("iut_ordered_dithering"), suffixe))
+            } else if palette.is_empty() {
+                ("ordered", avec_seuils("iut_ordered_dithering"))
+            } else {
+                ("orderedPal", format!("{}_{}", avec_seuils("iut_ordered_dithering_palette"), suffixe))
+            }
+        }
+        Mode::Yliluoma => {
+            if palette.is_empty() {
+                return Err("Pour le mode 'yliluoma', fournissez une palette de couleurs (ex: 'cyan,green,yellow')".into());
+            }
+            let nom = match options.yliluoma {
+                AlgorithmeYliluoma::Un => "yliluoma1",
+                AlgorithmeYliluoma::Deux => "yliluoma2",
+                AlgorithmeYliluoma::Trois => "yliluoma3",
+            };
+            (nom, format!("{}_{}", avec_seuils(&format!("iut_{}", nom)), suffixe))
+        }
+        Mode::Dbs => {
+            if gris {
+                return Err("Le mode 'dbs' trame en noir et blanc, l'option '--levels' ne s'y applique pas".into());
+            }
+            (options.dbs_start.as_str(), format!("iut_dbs_{}", options.dbs_start))
         }
         Mode::Error => {
-            let palette: Vec<&str> = couleurs.split(',').collect();
-
-            if !["simple"].contains(&erreur_diffuse) {
-                if palette.iter().all(|&color| color.is_empty()) {
-                    return Err("Pour le mode 'error', fournissez une palette de couleurs (ex: 'cyan,green,yellow')".into());
-                }
+            let simple = noyau.is_none() && matches!(erreur_diffuse, "simple" | "riemersma");
+            if !simple && palette.is_empty() {
+                return Err("Pour le mode 'error', fournissez une palette de couleurs (ex: 'cyan,green,yellow')".into());
             }
 
-            match erreur_diffuse {
-                "simple" => diffusion_d_erreur_simple(&chemin_img, &dossier_ecriture)?,
-                "simplePal" => diffusion_d_erreur_simple_palette(&chemin_img, &palette, &dossier_ecriture)?,
-                "floyd" => diffusion_d_erreur_floyd_steinberg_palette(&chemin_img, &palette, &dossier_ecriture)?,
-                "jjn" => diffusion_d_erreur_palette_matrice(&chemin_img, &palette, &jarvis_judice_ninke, 48, &dossier_ecriture)?,
-                "atkinson" => diffusion_d_erreur_palette_matrice(&chemin_img, &palette, &atkinson, 8, &dossier_ecriture)?,
+            let prefixe = match erreur_diffuse {
+                nom if noyau.is_some() => format!("iut_diffusion_d_erreur_palette_matrice_{}", nom),
+                "simple" => String::from("iut_diffusion_d_erreur_simple"),
+                "simplePal" => String::from("iut_diffusion_d_erreur_simple_palette"),
+                "floyd" => String::from("iut_diffusion_d_erreur_floyd_steinberg_palette"),
+                "riemersma" => String::from("iut_riemersma"),
+                "riemersmaPal" => String::from("iut_riemersma_palette"),
+                "ostromoukhov" => String::from("iut_diffusion_d_erreur_ostromoukhov_palette"),
+                "zhouFang" => String::from("iut_diffusion_d_erreur_zhou_fang_palette"),
+                nom if DiffusionMatrice::catalogue().iter().any(|noyau| noyau.nom == nom) => {
+                    format!("iut_diffusion_d_erreur_palette_matrice_{}", nom)
+                }
                 _ => return Err("Diffusion d'erreur non supportée".into()),
             };
-        },
+            if simple && !gris {
+                (erreur_diffuse, prefixe)
+            } else {
+                (erreur_diffuse, format!("{}_{}", prefixe, suffixe))
+            }
+        }
+    };
+
+    let reglages = Reglages {
+        lineaire: options.linear,
+        ecretage: options.clamp_error,
+        serpentin: options.serpentine,
+        luma: options.luma,
+    };
+    let niveaux = options.levels.clone().unwrap_or_default();
+    let mut registre = Registre::avec_reglages(reglages);
+    registre.enregistrer(Box::new(Monochrome { seuil: options.threshold, fenetre: options.window, reglages }));
+    registre.enregistrer(Box::new(TramageAleatoire {
+        bruit: options.noise,
+        graine: options.seed,
+        niveaux: niveaux.clone(),
+        reglages,
+    }));
+    registre.enregistrer(Box::new(TramageAleatoirePalette {
+        bruit: options.noise,
+        graine: options.seed,
+        ecart: options.spread,
+        reglages,
+    }));
+    registre.enregistrer(Box::new(TramageOrdonnePalette { seuils: seuils.clone(), ecart: options.spread, reglages }));
+    registre.enregistrer(Box::new(TramageMotif { seuils: seuils.clone(), reglages }));
+    registre.enregistrer(Box::new(Yliluoma { algorithme: options.yliluoma, seuils: seuils.clone(), reglages }));
+    registre.enregistrer(Box::new(TramageOrdonne { seuils, niveaux: niveaux.clone(), reglages }));
+    registre.enregistrer(Box::new(DiffusionSimple { niveaux: niveaux.clone(), reglages }));
+    registre.enregistrer(Box::new(Riemersma { niveaux, reglages }));
+    if let Some(noyau) = &noyau {
+        registre.enregistrer(Box::new(DiffusionMatrice { reglages, ..noyau.clone() }));
+    }
+    let ditherer = registre
+        .get(nom_ditherer)
+        .ok_or_else(|| format!("Algorithme inconnu : {}", nom_ditherer))?;
+
+    if matches!(mode, Mode::Mono) && !matches!(options.threshold, MethodeSeuil::Fixe(_)) {
+        if let Some(seuil) = seuil_automatique(&img, options.threshold, &reglages) {
+            println!("Seuil de luminance calculé : {:.1}", seuil);
+        }
+    }
+    let resultat = match mode {
+        Mode::Dbs => {
+            let depart = ditherer.dither(&img, &palette_depart());
+            let (resultat, rapport) = recherche_binaire_directe(&img, &depart, options.iterations, &reglages);
+            println!("Erreur perçue de départ ({}) : {:.6}", nom_ditherer, rapport.erreur_initiale);
+            for (numero, passe) in rapport.passes.iter().enumerate() {
+                println!(
+                    "Passe {} : {} basculements, {} échanges, erreur perçue {:.6}",
+                    numero + 1,
+                    passe.basculements,
+                    passe.echanges,
+                    passe.erreur
+                );
+            }
+            resultat
+        }
+        // Les algorithmes sur palette trament l'image convertie en gris vers la palette des niveaux ;
+        // les algorithmes noir et blanc calculent eux-mêmes la luminance.
+        _ if gris && !matches!(nom_ditherer, "randTram" | "ordered" | "simple" | "riemersma") => {
+            ditherer.dither(&en_gris(&img, &reglages), &palette)
+        }
+        _ => ditherer.dither(&img, &palette),
+    };
+    if gris {
+        // Le PNG garde les niveaux exacts, que la compression JPEG brouillerait.
+        image_grise(&resultat).save(format!("{}{}.png", dossier_ecriture, nom_fichier))?;
+    } else {
+        resultat.save(format!("{}{}.jpg", dossier_ecriture, nom_fichier))?;
     }
 
     println!(
@@ -0,0 +1,157 @@
+//! Niveaux de gris des tramages en N tons.
+//!
+//! Les algorithmes noir et blanc peuvent ramener l'image à plus de deux gris, régulièrement espacés
+//! (4 niveaux pour une liseuse à encre électronique, 16 pour un écran LCD...) ou choisis un par un.
+//! Les algorithmes sur palette obtiennent le même résultat sur l'image convertie par [`en_gris`],
+//! avec la palette [`NiveauxGris::palette`].
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use image::{GrayImage, Luma, Rgb, RgbImage};
+
+use crate::ditherer::Reglages;
+use crate::palette::Palette;
+use crate::tampon::{depuis_travail, vers_travail};
+
+/// Erreur lors de la construction des niveaux de gris.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurNiveaux {
+    /// Le nombre de niveaux n'est pas entre 2 et 256.
+    NombreInvalide(usize),
+    /// Un niveau n'est pas un entier entre 0 et 255.
+    NiveauInvalide(String),
+}
+
+impl fmt::Display for ErreurNiveaux {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurNiveaux::NombreInvalide(nombre) => {
+                write!(f, "Nombre de niveaux de gris invalide : {} (entre 2 et 256 niveaux distincts)", nombre)
+            }
+            ErreurNiveaux::NiveauInvalide(niveau) => {
+                write!(f, "Niveau de gris invalide : {} (entier entre 0 et 255)", niveau)
+            }
+        }
+    }
+}
+
+impl Error for ErreurNiveaux {}
+
+/// Gris de sortie d'un tramage en N tons, valeurs sRGB triées et distinctes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NiveauxGris {
+    niveaux: Vec<u8>,
+}
+
+impl Default for NiveauxGris {
+    /// Noir et blanc.
+    fn default() -> Self {
+        NiveauxGris { niveaux: vec![0, 255] }
+    }
+}
+
+impl NiveauxGris {
+    /// `nombre` gris régulièrement espacés du noir au blanc, 2 <= `nombre` <= 256.
+    pub fn uniformes(nombre: usize) -> Result<Self, ErreurNiveaux> {
+        if !(2..=256).contains(&nombre) {
+            return Err(ErreurNiveaux::NombreInvalide(nombre));
+        }
+        let niveaux = (0..nombre).map(|i| (i as f32 * 255.0 / (nombre - 1) as f32).round() as u8).collect();
+        Ok(NiveauxGris { niveaux })
+    }
+
+    /// Gris donnés, dans n'importe quel ordre. Les doublons sont retirés ; il doit en rester au moins deux.
+    pub fn new(mut niveaux: Vec<u8>) -> Result<Self, ErreurNiveaux> {
+        niveaux.sort_unstable();
+        niveaux.dedup();
+        if niveaux.len() < 2 {
+            return Err(ErreurNiveaux::NombreInvalide(niveaux.len()));
+        }
+        Ok(NiveauxGris { niveaux })
+    }
+
+    pub fn niveaux(&self) -> &[u8] {
+        &self.niveaux
+    }
+
+    pub fn len(&self) -> usize {
+        self.niveaux.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.niveaux.is_empty()
+    }
+
+    /// Vrai si les niveaux sont ceux de [`NiveauxGris::uniformes`].
+    pub fn sont_uniformes(&self) -> bool {
+        NiveauxGris::uniformes(self.len()).is_ok_and(|uniformes| uniformes == *self)
+    }
+
+    /// Palette des gris, pour les algorithmes travaillant sur une palette.
+    pub fn palette(&self) -> Palette {
+        Palette::new(self.niveaux.iter().map(|&gris| Rgb([gris, gris, gris])).collect())
+    }
+
+    /// Niveaux encadrant `valeur`, une valeur de travail (voir [`Reglages::gris`]), et position de
+    /// `valeur` entre les deux, de 0 sur le niveau bas à 1 sur le niveau haut.
+    ///
+    /// En dehors des niveaux, la position est ramenée à 0 ou à 1.
+    pub fn encadrer(&self, valeur: f32, lineaire: bool) -> (u8, u8, f32) {
+        let suivant = self.niveaux[1..]
+            .iter()
+            .position(|&niveau| valeur < vers_travail(niveau, lineaire))
+            .unwrap_or(self.niveaux.len() - 2);
+        let (bas, haut) = (self.niveaux[suivant], self.niveaux[suivant + 1]);
+        let (min, max) = (vers_travail(bas, lineaire), vers_travail(haut, lineaire));
+        (bas, haut, ((valeur - min) / (max - min)).clamp(0.0, 1.0))
+    }
+
+    /// Niveau le plus proche de la valeur de travail `valeur`.
+    pub fn plus_proche(&self, valeur: f32, lineaire: bool) -> u8 {
+        let (bas, haut, position) = self.encadrer(valeur, lineaire);
+        if position > 0.5 {
+            haut
+        } else {
+            bas
+        }
+    }
+}
+
+impl FromStr for NiveauxGris {
+    type Err = ErreurNiveaux;
+
+    /// Lit un nombre de niveaux régulièrement espacés (`4`), ou une liste de gris séparés par des
+    /// virgules (`0,96,176,255`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if !s.contains(',') {
+            let nombre = s.parse().map_err(|_| ErreurNiveaux::NiveauInvalide(s.to_string()))?;
+            return NiveauxGris::uniformes(nombre);
+        }
+        let niveaux = s
+            .split(',')
+            .map(|niveau| niveau.trim().parse().map_err(|_| ErreurNiveaux::NiveauInvalide(niveau.trim().to_string())))
+            .collect::<Result<_, _>>()?;
+        NiveauxGris::new(niveaux)
+    }
+}
+
+/// Convertit l'image en gris selon `reglages.luma`, sur les trois composantes.
+///
+/// Avec `reglages.lineaire`, le gris calculé en lumière linéaire est réencodé en sRGB.
+pub fn en_gris(img: &RgbImage, reglages: &Reglages) -> RgbImage {
+    let mut img = img.clone();
+    for pixel in img.pixels_mut() {
+        let gris = depuis_travail(reglages.gris(pixel), reglages.lineaire);
+        *pixel = Rgb([gris, gris, gris]);
+    }
+    img
+}
+
+/// Image en niveaux de gris 8 bits tirée de la composante rouge d'une image grise, comme celles
+/// produites par les tramages en N tons.
+pub fn image_grise(img: &RgbImage) -> GrayImage {
+    GrayImage::from_fn(img.width(), img.height(), |x, y| Luma([img.get_pixel(x, y)[0]]))
+}
@@ -0,0 +1,99 @@
+use image::Rgb;
+use std::str::FromStr;
+
+use crate::couleur::{decouper_couleurs, string_to_rgb8, ErreurCouleur};
+use crate::distance::Distance;
+
+/// Ensemble de couleurs vers lequel une image est ramenée lors du tramage,
+/// avec la métrique utilisée pour trouver la couleur la plus proche.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Palette {
+    couleurs: Vec<Rgb<u8>>,
+    distance: Distance,
+    /// Couleurs converties dans l'espace de la métrique, calculées une seule fois.
+    coordonnees: Vec<[f32; 3]>,
+}
+
+impl Palette {
+    /// Palette utilisant la distance euclidienne en sRGB.
+    pub fn new(couleurs: Vec<Rgb<u8>>) -> Self {
+        let distance = Distance::default();
+        let coordonnees = couleurs.iter().map(|c| distance.coordonnees(c)).collect();
+        Palette { couleurs, distance, coordonnees }
+    }
+
+    /// Change la métrique utilisée par [`Palette::plus_proche`].
+    pub fn avec_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self.coordonnees = self.couleurs.iter().map(|c| distance.coordonnees(c)).collect();
+        self
+    }
+
+    /// Palette noir et blanc utilisée par les algorithmes monochromes.
+    pub fn noir_et_blanc() -> Self {
+        Palette::new(vec![Rgb([0, 0, 0]), Rgb([255, 255, 255])])
+    }
+
+    pub fn couleurs(&self) -> &[Rgb<u8>] {
+        &self.couleurs
+    }
+
+    pub fn len(&self) -> usize {
+        self.couleurs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.couleurs.is_empty()
+    }
+
+    pub fn distance(&self) -> Distance {
+        self.distance
+    }
+
+    /// Renvoie la couleur de la palette la plus proche de `pixel` selon la métrique de la palette.
+    pub fn plus_proche(&self, pixel: &Rgb<u8>) -> Rgb<u8> {
+        let cible = self.distance.coordonnees(pixel);
+        let mut min_d = f32::MAX;
+        let mut min_couleur = Rgb([0, 0, 0]);
+        for (couleur, coordonnees) in self.couleurs.iter().zip(&self.coordonnees) {
+            let d = self.distance.ecart(&cible, coordonnees);
+            if d < min_d {
+                min_d = d;
+                min_couleur = *couleur;
+            }
+        }
+        min_couleur
+    }
+}
+
+impl From<Vec<Rgb<u8>>> for Palette {
+    fn from(couleurs: Vec<Rgb<u8>>) -> Self {
+        Palette::new(couleurs)
+    }
+}
+
+impl Extend<Rgb<u8>> for Palette {
+    fn extend<T: IntoIterator<Item = Rgb<u8>>>(&mut self, couleurs: T) {
+        for couleur in couleurs {
+            self.coordonnees.push(self.distance.coordonnees(&couleur));
+            self.couleurs.push(couleur);
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = ErreurCouleur;
+
+    /// Lit une liste de couleurs séparées par des virgules (voir [`string_to_rgb8`]).
+    /// Une chaîne vide donne une palette vide.
+    fn from_str(liste: &str) -> Result<Self, Self::Err> {
+        if liste.trim().is_empty() {
+            return Ok(Palette::default());
+        }
+        decouper_couleurs(liste)
+            .into_iter()
+            .map(string_to_rgb8)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Palette::new)
+    }
+}
@@ -0,0 +1,157 @@
+//! Palettes prédéfinies, sélectionnables par leur nom.
+
+use image::Rgb;
+
+use crate::palette::Palette;
+
+/// Palette fixe connue par son nom.
+#[derive(Debug, Clone, Copy)]
+pub struct Preset {
+    pub nom: &'static str,
+    pub description: &'static str,
+    pub couleurs: &'static [[u8; 3]],
+}
+
+impl Preset {
+    pub fn palette(&self) -> Palette {
+        Palette::new(self.couleurs.iter().map(|&rgb| Rgb(rgb)).collect())
+    }
+}
+
+/// Palettes à nombre de couleurs fixe. Les palettes paramétrées (`web-safe`,
+/// `grayscale-N`) sont construites à la demande par [`preset`].
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        nom: "gameboy",
+        description: "Nintendo Game Boy (DMG), quatre verts",
+        couleurs: &[
+            [15, 56, 15], [48, 98, 48], [139, 172, 15], [155, 188, 15],
+        ],
+    },
+    Preset {
+        nom: "cga0",
+        description: "CGA, palette 0 en basse intensité",
+        couleurs: &[
+            [0, 0, 0], [0, 170, 0], [170, 0, 0], [170, 85, 0],
+        ],
+    },
+    Preset {
+        nom: "cga0-high",
+        description: "CGA, palette 0 en haute intensité",
+        couleurs: &[
+            [0, 0, 0], [85, 255, 85], [255, 85, 85], [255, 255, 85],
+        ],
+    },
+    Preset {
+        nom: "cga1",
+        description: "CGA, palette 1 en basse intensité",
+        couleurs: &[
+            [0, 0, 0], [0, 170, 170], [170, 0, 170], [170, 170, 170],
+        ],
+    },
+    Preset {
+        nom: "cga1-high",
+        description: "CGA, palette 1 en haute intensité",
+        couleurs: &[
+            [0, 0, 0], [85, 255, 255], [255, 85, 255], [255, 255, 255],
+        ],
+    },
+    Preset {
+        nom: "ega",
+        description: "EGA, les 16 couleurs par défaut",
+        couleurs: &[
+            [0, 0, 0], [0, 0, 170], [0, 170, 0], [0, 170, 170],
+            [170, 0, 0], [170, 0, 170], [170, 85, 0], [170, 170, 170],
+            [85, 85, 85], [85, 85, 255], [85, 255, 85], [85, 255, 255],
+            [255, 85, 85], [255, 85, 255], [255, 255, 85], [255, 255, 255],
+        ],
+    },
+    Preset {
+        nom: "c64",
+        description: "Commodore 64 (valeurs « Pepto »)",
+        couleurs: &[
+            [0, 0, 0], [255, 255, 255], [104, 55, 43], [112, 164, 178],
+            [111, 61, 134], [88, 141, 67], [53, 40, 121], [184, 199, 111],
+            [111, 79, 37], [67, 57, 0], [154, 103, 89], [68, 68, 68],
+            [108, 108, 108], [154, 210, 132], [108, 94, 181], [149, 149, 149],
+        ],
+    },
+    Preset {
+        nom: "pico8",
+        description: "PICO-8",
+        couleurs: &[
+            [0, 0, 0], [29, 43, 83], [126, 37, 83], [0, 135, 81],
+            [171, 82, 54], [95, 87, 79], [194, 195, 199], [255, 241, 232],
+            [255, 0, 77], [255, 163, 0], [255, 236, 39], [0, 228, 54],
+            [41, 173, 255], [131, 118, 156], [255, 119, 168], [255, 204, 170],
+        ],
+    },
+    Preset {
+        nom: "zxspectrum",
+        description: "ZX Spectrum, couleurs normales puis brillantes",
+        couleurs: &[
+            [0, 0, 0], [0, 0, 215], [215, 0, 0], [215, 0, 215],
+            [0, 215, 0], [0, 215, 215], [215, 215, 0], [215, 215, 215],
+            [0, 0, 255], [255, 0, 0], [255, 0, 255], [0, 255, 0],
+            [0, 255, 255], [255, 255, 0], [255, 255, 255],
+        ],
+    },
+    Preset {
+        nom: "mac2",
+        description: "Macintosh II, palette système 16 couleurs",
+        couleurs: &[
+            [255, 255, 255], [251, 243, 5], [255, 100, 3], [221, 9, 7],
+            [242, 8, 132], [71, 0, 165], [0, 0, 211], [2, 171, 234],
+            [31, 183, 20], [0, 100, 18], [86, 44, 5], [144, 113, 58],
+            [192, 192, 192], [128, 128, 128], [64, 64, 64], [0, 0, 0],
+        ],
+    },
+];
+
+/// Noms acceptés par [`preset`], palettes paramétrées comprises.
+pub fn noms_presets() -> Vec<&'static str> {
+    let mut noms: Vec<&'static str> = PRESETS.iter().map(|p| p.nom).collect();
+    noms.push("web-safe");
+    noms.push("grayscale-N");
+    noms
+}
+
+/// Renvoie la palette prédéfinie `nom` (insensible à la casse), ou `None` si elle n'existe pas.
+///
+/// En plus de [`PRESETS`], on trouve :
+/// - `web-safe` : les 216 couleurs « web », composantes multiples de 51,
+/// - `grayscale-N` (ou `greyscale-N`) : `N` gris régulièrement espacés du noir au blanc, 2 <= N <= 256.
+pub fn preset(nom: &str) -> Option<Palette> {
+    let nom = nom.trim().to_lowercase();
+
+    if let Some(preset) = PRESETS.iter().find(|p| p.nom == nom) {
+        return Some(preset.palette());
+    }
+
+    if nom == "web-safe" || nom == "websafe" {
+        let mut couleurs = Vec::with_capacity(216);
+        for r in 0..6u8 {
+            for g in 0..6u8 {
+                for b in 0..6u8 {
+                    couleurs.push(Rgb([r * 51, g * 51, b * 51]));
+                }
+            }
+        }
+        return Some(Palette::new(couleurs));
+    }
+
+    let niveaux = nom
+        .strip_prefix("grayscale-")
+        .or_else(|| nom.strip_prefix("greyscale-"))?
+        .parse::<u32>()
+        .ok()
+        .filter(|n| (2..=256).contains(n))?;
+    Some(Palette::new(
+        (0..niveaux)
+            .map(|i| {
+                let gris = (i as f32 * 255.0 / (niveaux - 1) as f32).round() as u8;
+                Rgb([gris, gris, gris])
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,463 @@
+//! Extraction automatique d'une palette à partir d'une image (quantification des couleurs).
+
+use image::{Rgb, RgbImage};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::conversion::{lab_vers_rgb, rgb_vers_lab};
+use crate::palette::Palette;
+
+/// Algorithme utilisé pour calculer la palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quantificateur {
+    /// Découpe médiane (Heckbert) : on coupe récursivement la boîte la plus étendue en son milieu.
+    #[default]
+    MedianCut,
+    /// K-moyennes dans l'espace CIELAB, initialisées par k-means++.
+    KMeans,
+    /// Arbre octal dont on fusionne les feuilles les moins peuplées.
+    Octree,
+    /// Quantificateur de Wu : découpe minimisant la variance à l'aide des moments cumulés.
+    Wu,
+}
+
+impl FromStr for Quantificateur {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "median" => Ok(Quantificateur::MedianCut),
+            "kmeans" => Ok(Quantificateur::KMeans),
+            "octree" => Ok(Quantificateur::Octree),
+            "wu" => Ok(Quantificateur::Wu),
+            _ => Err(format!("Quantificateur invalide: {}", s)),
+        }
+    }
+}
+
+/// Calcule une palette d'au plus `nombre` couleurs représentative de `img`.
+///
+/// La palette peut compter moins de couleurs si l'image en contient moins.
+pub fn extraire_palette(img: &RgbImage, nombre: usize, methode: Quantificateur) -> Palette {
+    let histogramme = histogramme(img);
+    if nombre == 0 || histogramme.is_empty() {
+        return Palette::default();
+    }
+    if histogramme.len() <= nombre {
+        return Palette::new(histogramme.into_iter().map(|(rgb, _)| Rgb(rgb)).collect());
+    }
+
+    let couleurs = match methode {
+        Quantificateur::MedianCut => median_cut(histogramme, nombre),
+        Quantificateur::KMeans => k_means(&histogramme, nombre),
+        Quantificateur::Octree => octree(&histogramme, nombre),
+        Quantificateur::Wu => wu(&histogramme, nombre),
+    };
+    Palette::new(couleurs)
+}
+
+/// Couleurs distinctes de l'image avec leur nombre d'occurrences, triées pour un résultat reproductible.
+fn histogramme(img: &RgbImage) -> Vec<([u8; 3], u32)> {
+    let mut compte: HashMap<[u8; 3], u32> = HashMap::new();
+    for pixel in img.pixels() {
+        *compte.entry(pixel.0).or_insert(0) += 1;
+    }
+    let mut histogramme: Vec<_> = compte.into_iter().collect();
+    histogramme.sort_unstable();
+    histogramme
+}
+
+/// Couleur moyenne d'un ensemble de couleurs pondérées.
+fn moyenne(couleurs: &[([u8; 3], u32)]) -> Rgb<u8> {
+    let mut somme = [0u64; 3];
+    let mut total = 0u64;
+    for (rgb, n) in couleurs {
+        for c in 0..3 {
+            somme[c] += rgb[c] as u64 * *n as u64;
+        }
+        total += *n as u64;
+    }
+    let total = total.max(1);
+    Rgb([
+        ((somme[0] + total / 2) / total) as u8,
+        ((somme[1] + total / 2) / total) as u8,
+        ((somme[2] + total / 2) / total) as u8,
+    ])
+}
+
+fn median_cut(histogramme: Vec<([u8; 3], u32)>, nombre: usize) -> Vec<Rgb<u8>> {
+    /// Composante la plus étendue d'une boîte et son étendue.
+    fn etendue(boite: &[([u8; 3], u32)]) -> (usize, u8) {
+        (0..3)
+            .map(|c| {
+                let min = boite.iter().map(|(rgb, _)| rgb[c]).min().unwrap_or(0);
+                let max = boite.iter().map(|(rgb, _)| rgb[c]).max().unwrap_or(0);
+                (c, max - min)
+            })
+            .max_by_key(|&(_, e)| e)
+            .unwrap_or((0, 0))
+    }
+
+    let mut boites = vec![histogramme];
+    while boites.len() < nombre {
+        // On coupe la boîte la plus étendue, pondérée par son nombre de pixels.
+        let candidate = boites
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .max_by_key(|(_, b)| {
+                let pixels: u64 = b.iter().map(|(_, n)| *n as u64).sum();
+                etendue(b).1 as u64 * pixels
+            })
+            .map(|(i, _)| i);
+        let Some(indice) = candidate else { break };
+
+        let mut boite = boites.swap_remove(indice);
+        let (composante, _) = etendue(&boite);
+        boite.sort_unstable_by_key(|(rgb, _)| rgb[composante]);
+
+        let moitie: u64 = boite.iter().map(|(_, n)| *n as u64).sum::<u64>() / 2;
+        let mut cumul = 0u64;
+        let mut coupure = boite.len() - 1;
+        for (i, (_, n)) in boite.iter().enumerate() {
+            cumul += *n as u64;
+            if cumul >= moitie {
+                coupure = i + 1;
+                break;
+            }
+        }
+        let coupure = coupure.clamp(1, boite.len() - 1);
+        let seconde = boite.split_off(coupure);
+        boites.push(boite);
+        boites.push(seconde);
+    }
+
+    boites.iter().map(|b| moyenne(b)).collect()
+}
+
+fn k_means(histogramme: &[([u8; 3], u32)], nombre: usize) -> Vec<Rgb<u8>> {
+    const ITERATIONS_MAX: usize = 30;
+
+    let points: Vec<([f32; 3], f32)> = histogramme
+        .iter()
+        .map(|(rgb, n)| (rgb_vers_lab(&Rgb(*rgb)), *n as f32))
+        .collect();
+    let distance = |a: &[f32; 3], b: &[f32; 3]| (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2);
+
+    // Initialisation k-means++ à graine fixe pour des palettes reproductibles.
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut centres: Vec<[f32; 3]> = Vec::with_capacity(nombre);
+    let premier = points.iter().max_by(|a, b| a.1.total_cmp(&b.1)).map(|p| p.0).unwrap_or_default();
+    centres.push(premier);
+    let mut distances: Vec<f32> = points.iter().map(|(p, _)| distance(p, &premier)).collect();
+    while centres.len() < nombre {
+        let total: f32 = distances.iter().zip(&points).map(|(d, (_, n))| d * n).sum();
+        if total <= 0.0 {
+            break;
+        }
+        let mut tirage = rng.gen::<f32>() * total;
+        let mut choisi = points.len() - 1;
+        for (i, (d, (_, n))) in distances.iter().zip(&points).enumerate() {
+            tirage -= d * n;
+            if tirage <= 0.0 {
+                choisi = i;
+                break;
+            }
+        }
+        let centre = points[choisi].0;
+        centres.push(centre);
+        for (d, (p, _)) in distances.iter_mut().zip(&points) {
+            *d = d.min(distance(p, &centre));
+        }
+    }
+
+    let mut affectations = vec![0usize; points.len()];
+    for _ in 0..ITERATIONS_MAX {
+        let mut change = false;
+        for (affectation, (p, _)) in affectations.iter_mut().zip(&points) {
+            let plus_proche = centres
+                .iter()
+                .enumerate()
+                .min_by(|a, b| distance(p, a.1).total_cmp(&distance(p, b.1)))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            if *affectation != plus_proche {
+                *affectation = plus_proche;
+                change = true;
+            }
+        }
+
+        let mut sommes = vec![([0.0f32; 3], 0.0f32); centres.len()];
+        for (&k, (p, n)) in affectations.iter().zip(&points) {
+            for (somme, composante) in sommes[k].0.iter_mut().zip(p) {
+                *somme += composante * n;
+            }
+            sommes[k].1 += n;
+        }
+        for (centre, (somme, poids)) in centres.iter_mut().zip(&sommes) {
+            if *poids > 0.0 {
+                *centre = [somme[0] / poids, somme[1] / poids, somme[2] / poids];
+            }
+        }
+
+        if !change {
+            break;
+        }
+    }
+
+    let mut couleurs: Vec<Rgb<u8>> = centres.into_iter().map(lab_vers_rgb).collect();
+    couleurs.sort_unstable_by_key(|c| c.0);
+    couleurs.dedup();
+    couleurs
+}
+
+fn octree(histogramme: &[([u8; 3], u32)], nombre: usize) -> Vec<Rgb<u8>> {
+    const PROFONDEUR: usize = 8;
+
+    #[derive(Default, Clone)]
+    struct Noeud {
+        somme: [u64; 3],
+        pixels: u64,
+        enfants: [Option<usize>; 8],
+        feuille: bool,
+    }
+
+    let mut noeuds = vec![Noeud::default()];
+    // Noeuds internes de chaque niveau, candidats à la fusion.
+    let mut niveaux: Vec<Vec<usize>> = vec![Vec::new(); PROFONDEUR];
+    let mut feuilles = 0usize;
+
+    for (rgb, n) in histogramme {
+        let mut courant = 0;
+        for niveau in 0..PROFONDEUR {
+            let decalage = 7 - niveau;
+            let indice = (((rgb[0] >> decalage) & 1) << 2 | ((rgb[1] >> decalage) & 1) << 1 | ((rgb[2] >> decalage) & 1)) as usize;
+            courant = match noeuds[courant].enfants[indice] {
+                Some(enfant) => enfant,
+                None => {
+                    noeuds.push(Noeud { feuille: niveau + 1 == PROFONDEUR, ..Noeud::default() });
+                    let enfant = noeuds.len() - 1;
+                    noeuds[courant].enfants[indice] = Some(enfant);
+                    if niveau + 1 == PROFONDEUR {
+                        feuilles += 1;
+                    } else {
+                        niveaux[niveau + 1].push(enfant);
+                    }
+                    enfant
+                }
+            };
+        }
+        for (somme, &composante) in noeuds[courant].somme.iter_mut().zip(rgb) {
+            *somme += composante as u64 * *n as u64;
+        }
+        noeuds[courant].pixels += *n as u64;
+    }
+    niveaux[0].push(0);
+
+    // Cumule les sommes de chaque noeud interne depuis ses feuilles.
+    for niveau in (0..PROFONDEUR).rev() {
+        for &indice in &niveaux[niveau] {
+            let (mut somme, mut pixels) = ([0u64; 3], 0u64);
+            for enfant in noeuds[indice].enfants.iter().flatten() {
+                for (total, partiel) in somme.iter_mut().zip(noeuds[*enfant].somme) {
+                    *total += partiel;
+                }
+                pixels += noeuds[*enfant].pixels;
+            }
+            noeuds[indice].somme = somme;
+            noeuds[indice].pixels = pixels;
+        }
+    }
+
+    // Fusionne les noeuds les plus profonds, en commençant par les moins peuplés.
+    for niveau in (0..PROFONDEUR).rev() {
+        if feuilles <= nombre {
+            break;
+        }
+        let mut candidats = niveaux[niveau].clone();
+        candidats.sort_by_key(|&i| noeuds[i].pixels);
+        for indice in candidats {
+            if feuilles <= nombre {
+                break;
+            }
+            let enfants = noeuds[indice].enfants.iter().flatten().count();
+            noeuds[indice].enfants = [None; 8];
+            noeuds[indice].feuille = true;
+            feuilles = feuilles + 1 - enfants;
+        }
+    }
+
+    let mut couleurs = Vec::with_capacity(feuilles);
+    let mut pile = vec![0usize];
+    while let Some(indice) = pile.pop() {
+        let noeud = &noeuds[indice];
+        if noeud.feuille {
+            let pixels = noeud.pixels.max(1);
+            couleurs.push(Rgb([
+                ((noeud.somme[0] + pixels / 2) / pixels) as u8,
+                ((noeud.somme[1] + pixels / 2) / pixels) as u8,
+                ((noeud.somme[2] + pixels / 2) / pixels) as u8,
+            ]));
+        } else {
+            pile.extend(noeud.enfants.iter().flatten());
+        }
+    }
+    couleurs
+}
+
+fn wu(histogramme: &[([u8; 3], u32)], nombre: usize) -> Vec<Rgb<u8>> {
+    const TAILLE: usize = 33;
+    let indice = |r: usize, g: usize, b: usize| (r * TAILLE + g) * TAILLE + b;
+
+    // Moments d'ordre 0, 1 et 2 de l'histogramme 32x32x32, puis cumulés.
+    let mut poids = vec![0i64; TAILLE * TAILLE * TAILLE];
+    let mut moments = [vec![0i64; TAILLE * TAILLE * TAILLE], vec![0i64; TAILLE * TAILLE * TAILLE], vec![0i64; TAILLE * TAILLE * TAILLE]];
+    let mut carres = vec![0f64; TAILLE * TAILLE * TAILLE];
+    for (rgb, n) in histogramme {
+        let i = indice((rgb[0] >> 3) as usize + 1, (rgb[1] >> 3) as usize + 1, (rgb[2] >> 3) as usize + 1);
+        let n = *n as i64;
+        poids[i] += n;
+        for c in 0..3 {
+            moments[c][i] += rgb[c] as i64 * n;
+        }
+        carres[i] += n as f64 * rgb.iter().map(|&v| (v as f64).powi(2)).sum::<f64>();
+    }
+    for r in 1..TAILLE {
+        let mut zone_poids = [0i64; TAILLE];
+        let mut zone_moments = [[0i64; 3]; TAILLE];
+        let mut zone_carres = [0f64; TAILLE];
+        for g in 1..TAILLE {
+            let (mut ligne_poids, mut ligne_moments, mut ligne_carres) = (0i64, [0i64; 3], 0f64);
+            for b in 1..TAILLE {
+                let i = indice(r, g, b);
+                ligne_poids += poids[i];
+                for c in 0..3 {
+                    ligne_moments[c] += moments[c][i];
+                }
+                ligne_carres += carres[i];
+                zone_poids[b] += ligne_poids;
+                for c in 0..3 {
+                    zone_moments[b][c] += ligne_moments[c];
+                }
+                zone_carres[b] += ligne_carres;
+                let precedent = indice(r - 1, g, b);
+                poids[i] = poids[precedent] + zone_poids[b];
+                for c in 0..3 {
+                    moments[c][i] = moments[c][precedent] + zone_moments[b][c];
+                }
+                carres[i] = carres[precedent] + zone_carres[b];
+            }
+        }
+    }
+
+    /// Boîte de l'histogramme, bornes inférieures exclues.
+    #[derive(Clone, Copy, Default)]
+    struct Boite {
+        min: [usize; 3],
+        max: [usize; 3],
+    }
+
+    impl Boite {
+        fn volume(&self) -> usize {
+            (0..3).map(|c| self.max[c] - self.min[c]).product()
+        }
+    }
+
+    // Somme d'une table cumulée sur une boîte (inclusion-exclusion sur les 8 coins).
+    fn somme<T>(table: &[T], boite: &Boite, indice: impl Fn(usize, usize, usize) -> usize) -> T
+    where
+        T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T>,
+    {
+        let (r0, g0, b0) = (boite.min[0], boite.min[1], boite.min[2]);
+        let (r1, g1, b1) = (boite.max[0], boite.max[1], boite.max[2]);
+        table[indice(r1, g1, b1)] - table[indice(r1, g1, b0)] - table[indice(r1, g0, b1)] + table[indice(r1, g0, b0)]
+            - table[indice(r0, g1, b1)]
+            + table[indice(r0, g1, b0)]
+            + table[indice(r0, g0, b1)]
+            - table[indice(r0, g0, b0)]
+    }
+
+    let moment = |boite: &Boite, c: usize| somme(&moments[c], boite, indice);
+
+    let variance = |boite: &Boite| -> f64 {
+        let w = somme(&poids, boite, indice) as f64;
+        if w == 0.0 {
+            return 0.0;
+        }
+        let m: f64 = (0..3).map(|c| (moment(boite, c) as f64).powi(2)).sum();
+        somme(&carres, boite, indice) - m / w
+    };
+
+    // Meilleure coupe de `boite` le long de la composante `axe`, et son score.
+    let maximiser = |boite: &Boite, axe: usize| -> Option<(f64, usize)> {
+        let total_w = somme(&poids, boite, indice);
+        let total_m = [moment(boite, 0), moment(boite, 1), moment(boite, 2)];
+        let mut meilleur: Option<(f64, usize)> = None;
+        for coupe in boite.min[axe] + 1..boite.max[axe] {
+            let mut moitie = *boite;
+            moitie.max[axe] = coupe;
+            let w1 = somme(&poids, &moitie, indice);
+            let w2 = total_w - w1;
+            if w1 == 0 || w2 == 0 {
+                continue;
+            }
+            let m1 = [moment(&moitie, 0), moment(&moitie, 1), moment(&moitie, 2)];
+            let score1: f64 = m1.iter().map(|&m| (m as f64).powi(2)).sum::<f64>() / w1 as f64;
+            let score2: f64 = (0..3).map(|c| ((total_m[c] - m1[c]) as f64).powi(2)).sum::<f64>() / w2 as f64;
+            let score = score1 + score2;
+            if meilleur.is_none_or(|(s, _)| score > s) {
+                meilleur = Some((score, coupe));
+            }
+        }
+        meilleur
+    };
+
+    let mut boites = vec![Boite { min: [0; 3], max: [TAILLE - 1; 3] }];
+    let mut variances = vec![0.0f64];
+    let mut suivante = 0;
+    while boites.len() < nombre {
+        let boite = boites[suivante];
+        let coupe = (0..3)
+            .filter_map(|axe| maximiser(&boite, axe).map(|(score, coupe)| (score, axe, coupe)))
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+
+        match coupe {
+            Some((_, axe, position)) => {
+                let mut premiere = boite;
+                let mut seconde = boite;
+                premiere.max[axe] = position;
+                seconde.min[axe] = position;
+                boites[suivante] = premiere;
+                boites.push(seconde);
+                variances[suivante] = if premiere.volume() > 1 { variance(&premiere) } else { 0.0 };
+                variances.push(if seconde.volume() > 1 { variance(&seconde) } else { 0.0 });
+            }
+            None => variances[suivante] = 0.0,
+        }
+
+        let (indice_max, variance_max) = variances
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, v)| (i, *v))
+            .unwrap_or((0, 0.0));
+        if variance_max <= 0.0 {
+            break;
+        }
+        suivante = indice_max;
+    }
+
+    boites
+        .iter()
+        .filter_map(|boite| {
+            let w = somme(&poids, boite, indice);
+            if w == 0 {
+                return None;
+            }
+            let composante = |c: usize| ((moment(boite, c) as f64 / w as f64).round()).clamp(0.0, 255.0) as u8;
+            Some(Rgb([composante(0), composante(1), composante(2)]))
+        })
+        .collect()
+}
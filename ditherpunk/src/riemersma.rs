@@ -0,0 +1,218 @@
+//! Tramage de Riemersma : l'image est parcourue le long d'une courbe de Hilbert, généralisée aux
+//! rectangles pour ne visiter que les pixels de l'image, et chaque pixel est corrigé par
+//! l'historique des dernières erreurs, pondérées de façon décroissante.
+//!
+//! Contrairement aux diffusions ligne par ligne, l'erreur reste locale au trajet de la courbe,
+//! ce qui évite les traînées orientées.
+
+use std::collections::VecDeque;
+
+use image::{Rgb, RgbImage};
+
+use crate::ditherer::{Ditherer, Reglages};
+use crate::niveaux::NiveauxGris;
+use crate::palette::Palette;
+use crate::tampon::{depuis_travail, vers_travail, TamponErreur};
+
+/// Nombre d'erreurs gardées dans l'historique.
+const TAILLE_HISTORIQUE: usize = 16;
+
+/// Rapport entre le poids de l'erreur la plus récente et celui de la plus ancienne.
+const RAPPORT_POIDS: f32 = 16.0;
+
+/// Poids de l'historique, de l'erreur la plus ancienne à la plus récente, `RAPPORT_POIDS` fois plus
+/// lourde. Leur somme vaut 1, pour que toute l'erreur soit répartie le long de la courbe.
+fn poids_historique() -> [f32; TAILLE_HISTORIQUE] {
+    let facteur = RAPPORT_POIDS.ln() / (TAILLE_HISTORIQUE - 1) as f32;
+    let poids: [f32; TAILLE_HISTORIQUE] = std::array::from_fn(|i| (facteur * i as f32).exp());
+    let somme: f32 = poids.iter().sum();
+    poids.map(|p| p / somme)
+}
+
+/// Pixels d'une image `largeur × hauteur` dans l'ordre d'une courbe de Hilbert généralisée aux
+/// rectangles (algorithme « gilbert » de Jakub Červený) : chaque pixel est visité une fois, et deux
+/// pixels successifs sont voisins, sauf parfois en diagonale quand un côté est impair.
+fn parcours_gilbert(largeur: u32, hauteur: u32) -> Vec<(u32, u32)> {
+    let mut parcours = Vec::with_capacity(largeur as usize * hauteur as usize);
+    let (largeur, hauteur) = (largeur as i64, hauteur as i64);
+    if largeur == 0 || hauteur == 0 {
+        return parcours;
+    }
+    if largeur >= hauteur {
+        gilbert(&mut parcours, (0, 0), (largeur, 0), (0, hauteur));
+    } else {
+        gilbert(&mut parcours, (0, 0), (0, hauteur), (largeur, 0));
+    }
+    parcours
+}
+
+/// Remplit le rectangle d'origine `(x, y)`, de grand axe `a` et de petit axe `b`, en le coupant en
+/// deux ou trois rectangles parcourus dans le bon sens.
+fn gilbert(parcours: &mut Vec<(u32, u32)>, (x, y): (i64, i64), (ax, ay): (i64, i64), (bx, by): (i64, i64)) {
+    let (largeur, hauteur) = ((ax + ay).abs(), (bx + by).abs());
+    let (dax, day) = (ax.signum(), ay.signum());
+    let (dbx, dby) = (bx.signum(), by.signum());
+
+    if hauteur == 1 || largeur == 1 {
+        let (pas, longueur) = if hauteur == 1 { ((dax, day), largeur) } else { ((dbx, dby), hauteur) };
+        parcours.extend((0..longueur).map(|i| ((x + i * pas.0) as u32, (y + i * pas.1) as u32)));
+        return;
+    }
+
+    // Moitiés arrondies vers le bas, y compris pour les axes négatifs.
+    let (mut ax2, mut ay2) = (ax.div_euclid(2), ay.div_euclid(2));
+    let (mut bx2, mut by2) = (bx.div_euclid(2), by.div_euclid(2));
+    if 2 * largeur > 3 * hauteur {
+        // Rectangle allongé : deux moitiés le long du grand axe, de longueur paire si possible.
+        if (ax2 + ay2).abs() % 2 == 1 && largeur > 2 {
+            (ax2, ay2) = (ax2 + dax, ay2 + day);
+        }
+        gilbert(parcours, (x, y), (ax2, ay2), (bx, by));
+        gilbert(parcours, (x + ax2, y + ay2), (ax - ax2, ay - ay2), (bx, by));
+    } else {
+        // Sinon, comme la courbe de Hilbert : monter, traverser, redescendre.
+        if (bx2 + by2).abs() % 2 == 1 && hauteur > 2 {
+            (bx2, by2) = (bx2 + dbx, by2 + dby);
+        }
+        gilbert(parcours, (x, y), (bx2, by2), (ax2, ay2));
+        gilbert(parcours, (x + bx2, y + by2), (ax, ay), (bx - bx2, by - by2));
+        gilbert(
+            parcours,
+            (x + (ax - dax) + (bx2 - dbx), y + (ay - day) + (by2 - dby)),
+            (-bx2, -by2),
+            (-(ax - ax2), -(ay - ay2)),
+        );
+    }
+}
+
+/// Parcourt le `tampon` le long de la courbe : chaque pixel, corrigé par l'historique des erreurs,
+/// est remplacé par `quantifier(valeur corrigée)`, et l'écart entre la valeur corrigée et la couleur
+/// choisie entre dans l'historique. Les poids sommant à 1, l'erreur n'est ni perdue ni amplifiée et le
+/// ton moyen est conservé.
+fn riemersma(tampon: &TamponErreur, reglages: &Reglages, quantifier: impl Fn([f32; 3]) -> Rgb<u8>) -> RgbImage {
+    let (largeur, hauteur) = tampon.dimensions();
+    let mut sortie = RgbImage::new(largeur, hauteur);
+    let poids = poids_historique();
+    let mut historique: VecDeque<[f32; 3]> = VecDeque::from(vec![[0.0; 3]; TAILLE_HISTORIQUE]);
+
+    for (x, y) in parcours_gilbert(largeur, hauteur) {
+        let mut valeur = tampon.get(x, y);
+        for (erreur, poids) in historique.iter().zip(poids) {
+            for c in 0..3 {
+                valeur[c] += erreur[c] * poids;
+            }
+        }
+        if reglages.ecretage {
+            valeur = valeur.map(|v| v.clamp(0.0, 255.0));
+        }
+
+        let couleur = quantifier(valeur);
+        historique.pop_front();
+        historique.push_back(std::array::from_fn(|c| valeur[c] - vers_travail(couleur[c], reglages.lineaire)));
+        sortie.put_pixel(x, y, couleur);
+    }
+
+    sortie
+}
+
+/// Tramage de Riemersma en noir et blanc, sur la luminance.
+///
+/// Avec `reglages.lineaire`, la luminance et l'erreur sont calculées en lumière linéaire.
+pub fn tramage_riemersma(img: &RgbImage, reglages: &Reglages) -> RgbImage {
+    tramage_riemersma_niveaux(img, &NiveauxGris::default(), reglages)
+}
+
+/// Tramage de Riemersma en N niveaux de gris, sur la luminance.
+///
+/// Avec `reglages.lineaire`, la luminance et l'erreur sont calculées en lumière linéaire.
+pub fn tramage_riemersma_niveaux(img: &RgbImage, niveaux: &NiveauxGris, reglages: &Reglages) -> RgbImage {
+    let tampon = TamponErreur::depuis_image_gris(img, reglages.lineaire, |pixel| reglages.gris(pixel));
+    riemersma(&tampon, reglages, |valeur| {
+        let gris = niveaux.plus_proche(valeur[0], reglages.lineaire);
+        Rgb([gris, gris, gris])
+    })
+}
+
+/// Tramage de Riemersma vers la couleur la plus proche de la palette.
+pub fn tramage_riemersma_palette(img: &RgbImage, palette: &Palette, reglages: &Reglages) -> RgbImage {
+    let tampon = TamponErreur::depuis_image(img, reglages.lineaire);
+    riemersma(&tampon, reglages, |valeur| {
+        palette.plus_proche(&Rgb(valeur.map(|v| depuis_travail(v, reglages.lineaire))))
+    })
+}
+
+/// Tramage de Riemersma en niveaux de gris, noir et blanc par défaut, la palette est ignorée.
+#[derive(Debug, Clone, Default)]
+pub struct Riemersma {
+    pub niveaux: NiveauxGris,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for Riemersma {
+    fn nom(&self) -> &str {
+        "riemersma"
+    }
+
+    fn dither(&self, img: &RgbImage, _palette: &Palette) -> RgbImage {
+        tramage_riemersma_niveaux(img, &self.niveaux, &self.reglages)
+    }
+}
+
+/// Tramage de Riemersma vers la palette.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RiemersmaPalette {
+    pub reglages: Reglages,
+}
+
+impl Ditherer for RiemersmaPalette {
+    fn nom(&self) -> &str {
+        "riemersmaPal"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        tramage_riemersma_palette(img, palette, &self.reglages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn le_parcours_visite_chaque_pixel_une_fois() {
+        let tailles = [(1, 1), (1, 7), (7, 1), (2, 2), (3, 5), (8, 8), (17, 31), (64, 3), (100, 37), (4096, 16)];
+        for (largeur, hauteur) in tailles {
+            let parcours = parcours_gilbert(largeur, hauteur);
+            assert_eq!(parcours.len(), (largeur * hauteur) as usize, "{}×{}", largeur, hauteur);
+            let mut vus = vec![false; (largeur * hauteur) as usize];
+            for &(x, y) in &parcours {
+                assert!(x < largeur && y < hauteur, "{}×{} : ({}, {}) hors de l'image", largeur, hauteur, x, y);
+                let indice = (y * largeur + x) as usize;
+                assert!(!vus[indice], "{}×{} : ({}, {}) visité deux fois", largeur, hauteur, x, y);
+                vus[indice] = true;
+            }
+        }
+        assert!(parcours_gilbert(0, 10).is_empty());
+        assert!(parcours_gilbert(10, 0).is_empty());
+    }
+
+    #[test]
+    fn le_parcours_avance_de_voisin_en_voisin() {
+        for (largeur, hauteur) in [(8, 8), (16, 4), (32, 20), (4096, 16)] {
+            let parcours = parcours_gilbert(largeur, hauteur);
+            assert_eq!(parcours[0], (0, 0));
+            for paire in parcours.windows(2) {
+                let ((x0, y0), (x1, y1)) = (paire[0], paire[1]);
+                assert_eq!(x0.abs_diff(x1) + y0.abs_diff(y1), 1, "{}×{} : {:?}", largeur, hauteur, paire);
+            }
+        }
+    }
+
+    #[test]
+    fn les_poids_somment_a_un() {
+        let poids = poids_historique();
+        assert!((poids.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!(poids.windows(2).all(|paire| paire[0] < paire[1]));
+        assert!((poids[poids.len() - 1] / poids[0] - 16.0).abs() < 1e-3);
+    }
+}
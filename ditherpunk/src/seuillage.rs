@@ -0,0 +1,227 @@
+//! Seuillage monochrome : seuil fixe, seuils globaux calculés à partir de l'histogramme de
+//! luminance (moyenne, médiane, Otsu, Kapur) ou seuils locaux adaptatifs calculés sur une fenêtre
+//! autour de chaque pixel (Sauvola, Niblack, Bradley), utiles pour les documents numérisés.
+
+use std::str::FromStr;
+
+use image::{Rgb, RgbImage};
+
+use crate::ditherer::Reglages;
+
+/// Taille par défaut de la fenêtre des seuillages adaptatifs.
+pub const FENETRE_PAR_DEFAUT: u32 = 15;
+
+/// Coefficient `k` de Sauvola, avec la plage dynamique de l'écart type `R = 128`.
+const K_SAUVOLA: f64 = 0.2;
+
+/// Coefficient `k` de Niblack.
+const K_NIBLACK: f64 = -0.2;
+
+/// Part de la moyenne locale sous laquelle Bradley noircit un pixel.
+const T_BRADLEY: f64 = 0.15;
+
+/// Méthode de choix du seuil de luminance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MethodeSeuil {
+    /// Seuil donné, sur l'échelle 0..255.
+    Fixe(f32),
+    /// Luminance moyenne de l'image.
+    Moyenne,
+    /// Luminance médiane de l'image.
+    Mediane,
+    /// Méthode d'Otsu : maximise la variance entre les deux classes de l'histogramme.
+    Otsu,
+    /// Méthode de Kapur : maximise la somme des entropies des deux classes de l'histogramme.
+    Kapur,
+    /// Seuil local de Sauvola : `m × (1 + k × (s / R - 1))`.
+    Sauvola,
+    /// Seuil local de Niblack : `m + k × s`.
+    Niblack,
+    /// Seuil local de Bradley : `m × (1 - t)`.
+    Bradley,
+}
+
+impl Default for MethodeSeuil {
+    fn default() -> Self {
+        MethodeSeuil::Fixe(128.0)
+    }
+}
+
+impl FromStr for MethodeSeuil {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mean" => Ok(MethodeSeuil::Moyenne),
+            "median" => Ok(MethodeSeuil::Mediane),
+            "otsu" => Ok(MethodeSeuil::Otsu),
+            "kapur" => Ok(MethodeSeuil::Kapur),
+            "sauvola" => Ok(MethodeSeuil::Sauvola),
+            "niblack" => Ok(MethodeSeuil::Niblack),
+            "bradley" => Ok(MethodeSeuil::Bradley),
+            _ => match s.parse::<f32>() {
+                Ok(seuil) if (0.0..=255.0).contains(&seuil) => Ok(MethodeSeuil::Fixe(seuil)),
+                _ => Err(format!(
+                    "Seuil invalide : {} (nombre entre 0 et 255, mean, median, otsu, kapur, sauvola, niblack ou bradley)",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Histogramme des luminances arrondies.
+fn histogramme(lumas: &[f32]) -> [u64; 256] {
+    let mut histogramme = [0; 256];
+    for &luma in lumas {
+        histogramme[luma.round().clamp(0.0, 255.0) as usize] += 1;
+    }
+    histogramme
+}
+
+/// Seuil d'Otsu : le niveau `k` qui sépare le mieux `0..=k` de `k + 1..=255`.
+fn otsu(histogramme: &[u64; 256]) -> f32 {
+    let total: u64 = histogramme.iter().sum();
+    let somme_totale: f64 = histogramme.iter().enumerate().map(|(i, &n)| i as f64 * n as f64).sum();
+    let (mut poids, mut somme) = (0u64, 0.0f64);
+    let mut meilleur = (0, f64::MIN);
+    for (k, &n) in histogramme.iter().enumerate() {
+        poids += n;
+        somme += k as f64 * n as f64;
+        if poids == 0 || poids == total {
+            continue;
+        }
+        let (w0, w1) = (poids as f64, (total - poids) as f64);
+        let ecart = somme / w0 - (somme_totale - somme) / w1;
+        let variance = w0 * w1 * ecart * ecart;
+        if variance > meilleur.1 {
+            meilleur = (k, variance);
+        }
+    }
+    meilleur.0 as f32 + 0.5
+}
+
+/// Seuil de Kapur : le niveau `k` qui maximise la somme des entropies de `0..=k` et de `k + 1..=255`.
+fn kapur(histogramme: &[u64; 256]) -> f32 {
+    let total: u64 = histogramme.iter().sum();
+    let probabilites: Vec<f64> = histogramme.iter().map(|&n| n as f64 / total.max(1) as f64).collect();
+    let entropie = |classe: &[f64]| {
+        let masse: f64 = classe.iter().sum();
+        -classe.iter().filter(|&&p| p > 0.0).map(|&p| p / masse * (p / masse).ln()).sum::<f64>()
+    };
+    let mut meilleur = (0, f64::MIN);
+    for k in 0..255 {
+        let (bas, haut) = probabilites.split_at(k + 1);
+        if bas.iter().sum::<f64>() == 0.0 || haut.iter().sum::<f64>() == 0.0 {
+            continue;
+        }
+        let somme = entropie(bas) + entropie(haut);
+        if somme > meilleur.1 {
+            meilleur = (k, somme);
+        }
+    }
+    meilleur.0 as f32 + 0.5
+}
+
+/// Seuil global de l'image pour les méthodes globales, ou `None` pour les méthodes locales.
+///
+/// Avec `reglages.lineaire`, la luminance est calculée en lumière linéaire.
+pub fn seuil_automatique(img: &RgbImage, methode: MethodeSeuil, reglages: &Reglages) -> Option<f32> {
+    let lumas: Vec<f32> = img.pixels().map(|pixel| reglages.gris(pixel)).collect();
+    seuil_global(&lumas, methode)
+}
+
+fn seuil_global(lumas: &[f32], methode: MethodeSeuil) -> Option<f32> {
+    match methode {
+        MethodeSeuil::Fixe(seuil) => Some(seuil),
+        MethodeSeuil::Moyenne => Some(lumas.iter().sum::<f32>() / lumas.len().max(1) as f32),
+        MethodeSeuil::Mediane => {
+            let mut tries = lumas.to_vec();
+            tries.sort_by(f32::total_cmp);
+            Some(tries.get(tries.len() / 2).copied().unwrap_or(128.0))
+        }
+        MethodeSeuil::Otsu => Some(otsu(&histogramme(lumas))),
+        MethodeSeuil::Kapur => Some(kapur(&histogramme(lumas))),
+        MethodeSeuil::Sauvola | MethodeSeuil::Niblack | MethodeSeuil::Bradley => None,
+    }
+}
+
+/// Sommes cumulées des luminances et de leurs carrés, pour la moyenne et l'écart type d'une fenêtre
+/// en temps constant.
+struct ImageIntegrale {
+    largeur: usize,
+    sommes: Vec<f64>,
+    carres: Vec<f64>,
+}
+
+impl ImageIntegrale {
+    fn new(lumas: &[f32], largeur: usize, hauteur: usize) -> Self {
+        let cote = largeur + 1;
+        let mut sommes = vec![0.0; cote * (hauteur + 1)];
+        let mut carres = vec![0.0; cote * (hauteur + 1)];
+        for y in 0..hauteur {
+            let (mut ligne, mut ligne_carres) = (0.0, 0.0);
+            for x in 0..largeur {
+                let luma = lumas[y * largeur + x] as f64;
+                ligne += luma;
+                ligne_carres += luma * luma;
+                sommes[(y + 1) * cote + x + 1] = sommes[y * cote + x + 1] + ligne;
+                carres[(y + 1) * cote + x + 1] = carres[y * cote + x + 1] + ligne_carres;
+            }
+        }
+        ImageIntegrale { largeur, sommes, carres }
+    }
+
+    /// Moyenne et écart type sur le rectangle `x0..x1 × y0..y1`.
+    fn statistiques(&self, (x0, y0): (usize, usize), (x1, y1): (usize, usize)) -> (f64, f64) {
+        let cote = self.largeur + 1;
+        let rectangle = |t: &[f64]| t[y1 * cote + x1] - t[y0 * cote + x1] - t[y1 * cote + x0] + t[y0 * cote + x0];
+        let nombre = ((x1 - x0) * (y1 - y0)) as f64;
+        let moyenne = rectangle(&self.sommes) / nombre;
+        let variance = (rectangle(&self.carres) / nombre - moyenne * moyenne).max(0.0);
+        (moyenne, variance.sqrt())
+    }
+}
+
+/// Seuillage monochrome : chaque pixel prend `couleur1` si sa luminance dépasse le seuil choisi par
+/// `methode`, `couleur2` sinon.
+///
+/// Les méthodes locales calculent le seuil sur une fenêtre `fenetre × fenetre` centrée sur le pixel,
+/// rognée aux bords de l'image. Avec `reglages.lineaire`, la luminance est calculée en lumière linéaire.
+pub fn seuillage_par_paire(
+    img: &RgbImage,
+    couleur1: Rgb<u8>,
+    couleur2: Rgb<u8>,
+    methode: MethodeSeuil,
+    fenetre: u32,
+    reglages: &Reglages,
+) -> RgbImage {
+    let (largeur, hauteur) = (img.width() as usize, img.height() as usize);
+    let lumas: Vec<f32> = img.pixels().map(|pixel| reglages.gris(pixel)).collect();
+    let global = seuil_global(&lumas, methode);
+    let integrale = if global.is_none() { Some(ImageIntegrale::new(&lumas, largeur, hauteur)) } else { None };
+    let demi = (fenetre.max(1) / 2) as usize;
+
+    RgbImage::from_fn(img.width(), img.height(), |x, y| {
+        let (x, y) = (x as usize, y as usize);
+        let seuil = match (global, &integrale) {
+            (Some(seuil), _) => seuil as f64,
+            (None, Some(integrale)) => {
+                let debut = (x.saturating_sub(demi), y.saturating_sub(demi));
+                let fin = ((x + demi + 1).min(largeur), (y + demi + 1).min(hauteur));
+                let (moyenne, ecart_type) = integrale.statistiques(debut, fin);
+                match methode {
+                    MethodeSeuil::Sauvola => moyenne * (1.0 + K_SAUVOLA * (ecart_type / 128.0 - 1.0)),
+                    MethodeSeuil::Niblack => moyenne + K_NIBLACK * ecart_type,
+                    _ => moyenne * (1.0 - T_BRADLEY),
+                }
+            }
+            (None, None) => 128.0,
+        };
+        if lumas[y * largeur + x] as f64 > seuil {
+            couleur1
+        } else {
+            couleur2
+        }
+    })
+}
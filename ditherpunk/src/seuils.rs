@@ -0,0 +1,278 @@
+//! Cartes de seuils du tramage ordonné.
+//!
+//! Une carte associe à chaque position, répétée en mosaïque sur l'image, un seuil entre 0 et 1.
+//! Elle peut venir d'une matrice de Bayer, d'une trame à points groupés, d'une tuile de bruit bleu
+//! générée par l'algorithme void-and-cluster de Robert Ulichney, ou de n'importe quelle image en
+//! niveaux de gris.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use image::GrayImage;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::bayer::MatriceBayer;
+use crate::trame::MatriceTrame;
+
+/// Taille maximale d'une tuile de bruit bleu (le coût de génération croît comme taille⁴).
+pub const TAILLE_MAX_BRUIT_BLEU: usize = 128;
+
+/// Écart type du filtre gaussien mesurant la densité des points dans void-and-cluster.
+const SIGMA_VOID_AND_CLUSTER: f32 = 1.5;
+
+/// Proportion de points du motif initial de void-and-cluster.
+const PROPORTION_INITIALE: f32 = 0.1;
+
+/// Erreur lors de la construction d'une carte de seuils.
+#[derive(Debug)]
+pub enum ErreurSeuils {
+    /// L'image n'a pas pu être lue.
+    Lecture(image::ImageError),
+    /// L'image ne contient aucun pixel.
+    ImageVide,
+    /// La taille de la tuile de bruit bleu est nulle ou dépasse [`TAILLE_MAX_BRUIT_BLEU`].
+    TailleInvalide(usize),
+}
+
+impl fmt::Display for ErreurSeuils {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurSeuils::Lecture(erreur) => write!(f, "Lecture de la carte de seuils impossible : {}", erreur),
+            ErreurSeuils::ImageVide => write!(f, "La carte de seuils ne contient aucun pixel"),
+            ErreurSeuils::TailleInvalide(taille) => write!(
+                f,
+                "Taille de bruit bleu invalide : {} (entre 1 et {})",
+                taille, TAILLE_MAX_BRUIT_BLEU
+            ),
+        }
+    }
+}
+
+impl Error for ErreurSeuils {}
+
+impl From<image::ImageError> for ErreurSeuils {
+    fn from(erreur: image::ImageError) -> Self {
+        ErreurSeuils::Lecture(erreur)
+    }
+}
+
+/// Carte de seuils, répétée en mosaïque sur l'image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CarteSeuils {
+    pub largeur: usize,
+    pub hauteur: usize,
+    /// Nombre de seuils distincts, qui borne le nombre de niveaux qu'un motif peut reproduire.
+    pub niveaux: usize,
+    seuils: Vec<f32>,
+}
+
+impl Default for CarteSeuils {
+    /// Matrice de Bayer 8×8.
+    fn default() -> Self {
+        CarteSeuils::from(&MatriceBayer::default())
+    }
+}
+
+impl From<&MatriceBayer> for CarteSeuils {
+    fn from(bayer: &MatriceBayer) -> Self {
+        let rangs = bayer.matrice.iter().flatten().map(|&valeur| valeur as usize).collect();
+        CarteSeuils::depuis_rangs(bayer.taille, bayer.taille, rangs)
+    }
+}
+
+impl From<MatriceBayer> for CarteSeuils {
+    fn from(bayer: MatriceBayer) -> Self {
+        CarteSeuils::from(&bayer)
+    }
+}
+
+impl From<&MatriceTrame> for CarteSeuils {
+    fn from(trame: &MatriceTrame) -> Self {
+        let rangs = trame.matrice.iter().flatten().map(|&valeur| valeur as usize).collect();
+        CarteSeuils::depuis_rangs(trame.taille, trame.taille, rangs)
+    }
+}
+
+impl From<MatriceTrame> for CarteSeuils {
+    fn from(trame: MatriceTrame) -> Self {
+        CarteSeuils::from(&trame)
+    }
+}
+
+impl CarteSeuils {
+    /// Carte dont chaque case reçoit le seuil centré `(rang + 0.5) / (largeur × hauteur)`,
+    /// les rangs étant une permutation de `0..largeur × hauteur`.
+    fn depuis_rangs(largeur: usize, hauteur: usize, rangs: Vec<usize>) -> Self {
+        let total = (largeur * hauteur) as f32;
+        CarteSeuils {
+            largeur,
+            hauteur,
+            niveaux: largeur * hauteur,
+            seuils: rangs.into_iter().map(|rang| (rang as f32 + 0.5) / total).collect(),
+        }
+    }
+
+    /// Tuile de bruit bleu `taille × taille` générée par void-and-cluster, reproductible pour une même `graine`.
+    pub fn bruit_bleu(taille: usize, graine: u64) -> Result<Self, ErreurSeuils> {
+        if taille == 0 || taille > TAILLE_MAX_BRUIT_BLEU {
+            return Err(ErreurSeuils::TailleInvalide(taille));
+        }
+        Ok(CarteSeuils::depuis_rangs(taille, taille, void_and_cluster(taille, graine)))
+    }
+
+    /// Carte tirée d'une image en niveaux de gris : le gris `g` donne le seuil `(g + 0.5) / 256`.
+    pub fn depuis_image(image: &GrayImage) -> Result<Self, ErreurSeuils> {
+        if image.width() == 0 || image.height() == 0 {
+            return Err(ErreurSeuils::ImageVide);
+        }
+        let mut presents = [false; 256];
+        for pixel in image.pixels() {
+            presents[pixel[0] as usize] = true;
+        }
+        Ok(CarteSeuils {
+            largeur: image.width() as usize,
+            hauteur: image.height() as usize,
+            niveaux: presents.iter().filter(|&&present| present).count(),
+            seuils: image.pixels().map(|pixel| (pixel[0] as f32 + 0.5) / 256.0).collect(),
+        })
+    }
+
+    /// Charge une carte depuis un fichier image (PNG par exemple), converti en niveaux de gris.
+    pub fn charger(chemin: &Path) -> Result<Self, ErreurSeuils> {
+        CarteSeuils::depuis_image(&image::open(chemin)?.to_luma8())
+    }
+
+    /// Seuil du pixel `(x, y)`, entre 0 et 1.
+    pub fn seuil(&self, x: u32, y: u32) -> f32 {
+        self.seuils[(y as usize % self.hauteur) * self.largeur + x as usize % self.largeur]
+    }
+}
+
+/// Densité des points d'un motif binaire torique, mesurée par un filtre gaussien tronqué à trois
+/// écarts types.
+#[derive(Clone)]
+struct Energie {
+    taille: usize,
+    /// Décalages `(dx, dy)` toriques où le filtre n'est pas négligeable, avec leur poids.
+    filtre: Vec<(usize, usize, f32)>,
+    valeurs: Vec<f32>,
+}
+
+impl Energie {
+    fn new(taille: usize) -> Self {
+        let rayon = (3.0 * SIGMA_VOID_AND_CLUSTER).ceil();
+        let distance = |d: usize| d.min(taille - d) as f32;
+        let filtre = (0..taille * taille)
+            .map(|i| (i % taille, i / taille))
+            .filter(|&(dx, dy)| distance(dx) <= rayon && distance(dy) <= rayon)
+            .map(|(dx, dy)| {
+                let carre = distance(dx).powi(2) + distance(dy).powi(2);
+                (dx, dy, (-carre / (2.0 * SIGMA_VOID_AND_CLUSTER * SIGMA_VOID_AND_CLUSTER)).exp())
+            })
+            .collect();
+        Energie { taille, filtre, valeurs: vec![0.0; taille * taille] }
+    }
+
+    /// Cases touchées par le point `indice`, avec le poids qu'il leur ajoute.
+    fn voisines(&self, indice: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let (px, py) = (indice % self.taille, indice / self.taille);
+        self.filtre
+            .iter()
+            .map(move |&(dx, dy, poids)| ((py + dy) % self.taille * self.taille + (px + dx) % self.taille, poids))
+    }
+
+    /// Ajoute (`signe` = 1) ou retire (`signe` = -1) la contribution du point `indice`.
+    fn basculer(&mut self, indice: usize, signe: f32) {
+        for (voisine, poids) in self.voisines(indice).collect::<Vec<_>>() {
+            self.valeurs[voisine] += signe * poids;
+        }
+    }
+
+    /// Point le plus dense (amas le plus serré) parmi les cases valant `true`.
+    fn amas(&self, motif: &[bool]) -> usize {
+        (0..motif.len()).filter(|&i| motif[i]).max_by(|&a, &b| self.valeurs[a].total_cmp(&self.valeurs[b])).unwrap_or(0)
+    }
+
+    /// Case la moins dense (plus grand vide) parmi les cases valant `false`.
+    fn vide(&self, motif: &[bool]) -> usize {
+        (0..motif.len()).filter(|&i| !motif[i]).min_by(|&a, &b| self.valeurs[a].total_cmp(&self.valeurs[b])).unwrap_or(0)
+    }
+
+    /// Remplit une à une les cases libres de `motif`, chacune dans le plus grand vide laissé par les
+    /// précédentes (la première dans l'ordre de lecture en cas d'égalité), en leur donnant les rangs
+    /// `premier`, `premier + 1`...
+    fn remplir_vides(&mut self, motif: &mut [bool], rangs: &mut [usize], premier: usize) {
+        // Cases libres triées par densité, les densités positives se comparant comme leurs bits.
+        let cle = |valeur: f32, case: usize| (if valeur > 0.0 { valeur.to_bits() } else { 0 }, case);
+        let mut libres: BTreeSet<(u32, usize)> =
+            (0..motif.len()).filter(|&case| !motif[case]).map(|case| cle(self.valeurs[case], case)).collect();
+        for rang in premier..motif.len() {
+            let Some((_, case)) = libres.pop_first() else { break };
+            motif[case] = true;
+            rangs[case] = rang;
+            for (voisine, poids) in self.voisines(case).collect::<Vec<_>>() {
+                let libre = libres.remove(&cle(self.valeurs[voisine], voisine));
+                self.valeurs[voisine] += poids;
+                if libre {
+                    libres.insert(cle(self.valeurs[voisine], voisine));
+                }
+            }
+        }
+    }
+}
+
+/// Rangs d'une tuile `taille × taille` remplie point par point depuis un motif vide, chaque point
+/// tombant dans le plus grand vide laissé par les précédents : la dernière phase de void-and-cluster
+/// seule, sans hasard.
+pub(crate) fn vides_successifs(taille: usize) -> Vec<usize> {
+    let mut rangs = vec![0; taille * taille];
+    Energie::new(taille).remplir_vides(&mut vec![false; taille * taille], &mut rangs, 0);
+    rangs
+}
+
+/// Rangs de void-and-cluster pour une tuile `taille × taille`.
+///
+/// Un motif initial aléatoire est d'abord équilibré en déplaçant son point le plus dense vers le plus
+/// grand vide, jusqu'à ce que ce soit le même point. Les points de ce motif sont ensuite classés en
+/// retirant le plus dense à chaque fois, puis les cases restantes en remplissant le plus grand vide.
+fn void_and_cluster(taille: usize, graine: u64) -> Vec<usize> {
+    let total = taille * taille;
+    let initiaux = ((total as f32 * PROPORTION_INITIALE) as usize).max(1);
+
+    let mut indices: Vec<usize> = (0..total).collect();
+    indices.shuffle(&mut ChaCha8Rng::seed_from_u64(graine));
+    let mut motif = vec![false; total];
+    let mut energie = Energie::new(taille);
+    for &indice in &indices[..initiaux] {
+        motif[indice] = true;
+        energie.basculer(indice, 1.0);
+    }
+
+    for _ in 0..total {
+        let amas = energie.amas(&motif);
+        motif[amas] = false;
+        energie.basculer(amas, -1.0);
+        let vide = energie.vide(&motif);
+        motif[vide] = true;
+        energie.basculer(vide, 1.0);
+        if vide == amas {
+            break;
+        }
+    }
+
+    let mut rangs = vec![0; total];
+    let (mut retraits, mut energie_retraits) = (motif.clone(), energie.clone());
+    for rang in (0..initiaux).rev() {
+        let amas = energie_retraits.amas(&retraits);
+        retraits[amas] = false;
+        energie_retraits.basculer(amas, -1.0);
+        rangs[amas] = rang;
+    }
+
+    energie.remplir_vides(&mut motif, &mut rangs, initiaux);
+    rangs
+}
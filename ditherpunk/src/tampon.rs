@@ -0,0 +1,85 @@
+//! Tampon flottant sur lequel travaillent les algorithmes de diffusion d'erreur.
+
+use image::{Rgb, RgbImage};
+
+use crate::conversion::{lineaire_vers_srgb, srgb_vers_lineaire};
+
+/// Image en `f32` (échelle 0..=255) accumulant l'erreur diffusée sans perte d'arrondi.
+///
+/// Les valeurs sont en sRGB, ou en lumière linéaire ramenée sur 0..=255 si `lineaire`.
+pub(crate) struct TamponErreur {
+    largeur: u32,
+    hauteur: u32,
+    lineaire: bool,
+    valeurs: Vec<[f32; 3]>,
+}
+
+impl TamponErreur {
+    pub(crate) fn depuis_image(img: &RgbImage, lineaire: bool) -> Self {
+        let valeurs = img
+            .pixels()
+            .map(|pixel| [0, 1, 2].map(|c| vers_travail(pixel[c], lineaire)))
+            .collect();
+        TamponErreur { largeur: img.width(), hauteur: img.height(), lineaire, valeurs }
+    }
+
+    /// Tampon dont chaque pixel vaut `valeur(pixel)` sur les trois composantes.
+    pub(crate) fn depuis_image_gris(img: &RgbImage, lineaire: bool, valeur: impl Fn(&Rgb<u8>) -> f32) -> Self {
+        let valeurs = img.pixels().map(|pixel| [valeur(pixel); 3]).collect();
+        TamponErreur { largeur: img.width(), hauteur: img.height(), lineaire, valeurs }
+    }
+
+    pub(crate) fn dimensions(&self) -> (u32, u32) {
+        (self.largeur, self.hauteur)
+    }
+
+    pub(crate) fn get(&self, x: u32, y: u32) -> [f32; 3] {
+        self.valeurs[(y * self.largeur + x) as usize]
+    }
+
+    /// Valeur courante réencodée en sRGB 8 bits (écrêtée), pour la recherche dans une palette.
+    pub(crate) fn get_srgb(&self, x: u32, y: u32) -> Rgb<u8> {
+        Rgb(self.get(x, y).map(|v| depuis_travail(v, self.lineaire)))
+    }
+
+    /// Erreur commise en remplaçant le pixel `(x, y)` par `couleur`.
+    pub(crate) fn erreur(&self, x: u32, y: u32, couleur: &Rgb<u8>) -> [f32; 3] {
+        let valeur = self.get(x, y);
+        [0, 1, 2].map(|c| valeur[c] - vers_travail(couleur[c], self.lineaire))
+    }
+
+    /// Ajoute `poids * erreur` au pixel `(x, y)` s'il est dans l'image.
+    ///
+    /// Avec `ecretage`, le résultat est ramené entre 0 et 255 ; sinon l'erreur peut déborder
+    /// et sera compensée par les pixels suivants.
+    pub(crate) fn ajouter(&mut self, x: i64, y: i64, erreur: &[f32; 3], poids: f32, ecretage: bool) {
+        if x < 0 || y < 0 || x >= self.largeur as i64 || y >= self.hauteur as i64 {
+            return;
+        }
+        let valeur = &mut self.valeurs[(y as u32 * self.largeur + x as u32) as usize];
+        for c in 0..3 {
+            valeur[c] += erreur[c] * poids;
+            if ecretage {
+                valeur[c] = valeur[c].clamp(0.0, 255.0);
+            }
+        }
+    }
+}
+
+/// Valeur de travail d'une composante : sRGB brute, ou lumière linéaire ramenée sur 0..=255.
+pub(crate) fn vers_travail(valeur: u8, lineaire: bool) -> f32 {
+    if lineaire {
+        srgb_vers_lineaire(valeur) * 255.0
+    } else {
+        valeur as f32
+    }
+}
+
+/// Réencode une valeur de travail en sRGB 8 bits, écrêtée entre 0 et 255.
+pub(crate) fn depuis_travail(valeur: f32, lineaire: bool) -> u8 {
+    if lineaire {
+        lineaire_vers_srgb(valeur / 255.0)
+    } else {
+        valeur.round().clamp(0.0, 255.0) as u8
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use image::{Rgb, RgbImage};
+
+use crate::bruit::{Bruit, GenerateurBruit};
+use crate::conversion::luminance;
+use crate::ditherer::{Ditherer, Reglages};
+use crate::niveaux::NiveauxGris;
+use crate::palette::Palette;
+use crate::seuillage::{seuillage_par_paire, MethodeSeuil, FENETRE_PAR_DEFAUT};
+use crate::seuils::CarteSeuils;
+use crate::tampon::{depuis_travail, vers_travail};
+
+/// Nombre de couleurs candidates du tramage par motif.
+const TAILLE_MOTIF: usize = 16;
+
+/// Part de l'erreur accumulée reportée sur chaque nouvelle candidate du tramage par motif.
+const MULTIPLICATEUR_ERREUR_MOTIF: f32 = 0.5;
+
+/// Remplace chaque pixel par `couleur1` si sa luminance dépasse `seuil` (sur l'échelle 0..=255),
+/// par `couleur2` sinon. Voir [`seuillage_par_paire`] pour les seuils automatiques et locaux.
+///
+/// Avec `reglages.lineaire`, la luminance, selon `reglages.luma`, est calculée en lumière linéaire (voir [`Reglages::gris`]).
+pub fn monochrome_par_paire(
+    img: &RgbImage,
+    couleur1: Rgb<u8>,
+    couleur2: Rgb<u8>,
+    seuil: f32,
+    reglages: &Reglages,
+) -> RgbImage {
+    seuillage_par_paire(img, couleur1, couleur2, MethodeSeuil::Fixe(seuil), FENETRE_PAR_DEFAUT, reglages)
+}
+
+/// Remplace chaque pixel par la couleur la plus proche de la palette.
+pub fn passage_a_une_palette(img: &RgbImage, palette: &Palette) -> RgbImage {
+    let mut img = img.clone();
+    for pixel in img.pixels_mut() {
+        *pixel = palette.plus_proche(pixel);
+    }
+    img
+}
+
+/// Tramage aléatoire en noir et blanc : le bruit `bruit` est ajouté à la luminance, qui est ensuite
+/// comparée au seuil 0.5. Avec le bruit uniforme, cela revient à comparer la luminance à un seuil tiré au hasard.
+///
+/// Le tirage est reproductible quand une `graine` est donnée. Avec `reglages.lineaire`, la proportion
+/// de pixels blancs suit la luminance en lumière linéaire, ce qui conserve la luminosité moyenne de l'image.
+pub fn tramage_random(img: &RgbImage, bruit: Bruit, graine: Option<u64>, reglages: &Reglages) -> RgbImage {
+    tramage_random_niveaux(img, &NiveauxGris::default(), bruit, graine, reglages)
+}
+
+/// Tramage aléatoire en N niveaux de gris : chaque pixel prend l'un des deux niveaux qui encadrent sa
+/// luminance, le niveau haut si sa position entre les deux, plus le bruit, dépasse 0.5.
+///
+/// Voir [`tramage_random`] pour la graine et la lumière linéaire.
+pub fn tramage_random_niveaux(
+    img: &RgbImage,
+    niveaux: &NiveauxGris,
+    bruit: Bruit,
+    graine: Option<u64>,
+    reglages: &Reglages,
+) -> RgbImage {
+    let mut generateur = GenerateurBruit::new(bruit, graine);
+    let mut img = img.clone();
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let (bas, haut, position) = niveaux.encadrer(reglages.gris(pixel), reglages.lineaire);
+        let gris = if position + generateur.tirer(x, y) > 0.5 { haut } else { bas };
+        *pixel = Rgb([gris, gris, gris]);
+    }
+    img
+}
+
+/// Tramage aléatoire en couleur : chaque composante est décalée de `bruit × ecart`, puis le pixel
+/// prend la couleur la plus proche de la palette.
+///
+/// Le tirage est reproductible quand une `graine` est donnée. Avec `reglages.lineaire`, le décalage
+/// est appliqué en lumière linéaire.
+pub fn tramage_random_palette(
+    img: &RgbImage,
+    palette: &Palette,
+    bruit: Bruit,
+    graine: Option<u64>,
+    ecart: f32,
+    reglages: &Reglages,
+) -> RgbImage {
+    let mut generateur = GenerateurBruit::new(bruit, graine);
+    let mut img = img.clone();
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let decalage = generateur.tirer(x, y) * ecart;
+        let decale = pixel.0.map(|c| depuis_travail(vers_travail(c, reglages.lineaire) + decalage, reglages.lineaire));
+        *pixel = palette.plus_proche(&Rgb(decale));
+    }
+    img
+}
+
+/// Tramage ordonné en noir et blanc à l'aide de la carte `seuils` (matrice de Bayer, bruit bleu...).
+///
+/// Avec `reglages.lineaire`, la luminance comparée aux seuils est calculée en lumière linéaire.
+pub fn ordered_dithering(img: &RgbImage, seuils: &CarteSeuils, reglages: &Reglages) -> RgbImage {
+    ordered_dithering_niveaux(img, &NiveauxGris::default(), seuils, reglages)
+}
+
+/// Tramage ordonné en N niveaux de gris : chaque pixel prend l'un des deux niveaux qui encadrent sa
+/// luminance, le niveau haut si sa position entre les deux dépasse le seuil de la carte `seuils`.
+///
+/// Avec `reglages.lineaire`, la position est mesurée en lumière linéaire.
+pub fn ordered_dithering_niveaux(
+    img: &RgbImage,
+    niveaux: &NiveauxGris,
+    seuils: &CarteSeuils,
+    reglages: &Reglages,
+) -> RgbImage {
+    let mut img = img.clone();
+
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let (bas, haut, position) = niveaux.encadrer(reglages.gris(pixel), reglages.lineaire);
+        let gris = if position > seuils.seuil(x, y) { haut } else { bas };
+        *pixel = Rgb([gris, gris, gris]);
+    }
+
+    img
+}
+
+/// Écart par défaut des tramages ordonné et aléatoire en couleur : la distance moyenne entre les couleurs
+/// d'une palette de `n` couleurs réparties uniformément dans le cube RGB, soit `255 / ∛n`.
+pub fn ecart_par_defaut(palette: &Palette) -> f32 {
+    255.0 / (palette.len().max(1) as f32).cbrt()
+}
+
+/// Tramage ordonné en couleur : chaque composante est décalée de `(seuil - 0.5) * ecart`,
+/// le seuil venant de la carte `seuils`, puis le pixel prend la couleur la plus proche de la palette.
+///
+/// Le résultat ne dépend que du pixel et de sa position, ce qui le rend stable d'une image à l'autre
+/// d'une animation. Avec `reglages.lineaire`, le décalage est appliqué en lumière linéaire.
+pub fn ordered_dithering_palette(
+    img: &RgbImage,
+    palette: &Palette,
+    seuils: &CarteSeuils,
+    ecart: f32,
+    reglages: &Reglages,
+) -> RgbImage {
+    let mut img = img.clone();
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let decalage = (seuils.seuil(x, y) - 0.5) * ecart;
+        let decale = pixel.0.map(|c| depuis_travail(vers_travail(c, reglages.lineaire) + decalage, reglages.lineaire));
+        *pixel = palette.plus_proche(&Rgb(decale));
+    }
+    img
+}
+
+/// Couleurs candidates de l'algorithme de Thomas Knoll pour `pixel`, triées par luminance :
+/// chaque candidate est la couleur la plus proche du pixel corrigé par l'erreur accumulée.
+fn motif_knoll(pixel: &Rgb<u8>, palette: &Palette, lineaire: bool) -> Vec<Rgb<u8>> {
+    let cible = pixel.0.map(|c| vers_travail(c, lineaire));
+    let mut erreur = [0.0f32; 3];
+    let mut candidates: Vec<Rgb<u8>> = (0..TAILLE_MOTIF)
+        .map(|_| {
+            let essai = [0, 1, 2].map(|c| depuis_travail(cible[c] + erreur[c] * MULTIPLICATEUR_ERREUR_MOTIF, lineaire));
+            let candidate = palette.plus_proche(&Rgb(essai));
+            for c in 0..3 {
+                erreur[c] += cible[c] - vers_travail(candidate[c], lineaire);
+            }
+            candidate
+        })
+        .collect();
+    candidates.sort_by(|a, b| luminance(a, false).total_cmp(&luminance(b, false)));
+    candidates
+}
+
+/// Tramage ordonné par motif (algorithme de Thomas Knoll, tel que décrit par Joel Yliluoma) :
+/// pour chaque couleur, un mélange de candidates de la palette approchant cette couleur est calculé,
+/// et le seuil de la carte `seuils` choisit la candidate affichée.
+///
+/// Plus lent que [`ordered_dithering_palette`], mais fidèle aux teintes, sans écart à régler.
+pub fn ordered_dithering_motif(img: &RgbImage, palette: &Palette, seuils: &CarteSeuils, reglages: &Reglages) -> RgbImage {
+    let mut motifs: HashMap<Rgb<u8>, Vec<Rgb<u8>>> = HashMap::new();
+    let mut img = img.clone();
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let motif = motifs.entry(*pixel).or_insert_with(|| motif_knoll(pixel, palette, reglages.lineaire));
+        let indice = ((seuils.seuil(x, y) * TAILLE_MOTIF as f32) as usize).min(TAILLE_MOTIF - 1);
+        *pixel = motif[indice];
+    }
+    img
+}
+
+/// Seuillage monochrome utilisant les deux premières couleurs de la palette
+/// (la première pour les zones claires), ou blanc et noir si elle en compte moins de deux.
+#[derive(Debug, Clone, Copy)]
+pub struct Monochrome {
+    /// Choix du seuil, 128 par défaut.
+    pub seuil: MethodeSeuil,
+    /// Taille de la fenêtre des seuils locaux.
+    pub fenetre: u32,
+    pub reglages: Reglages,
+}
+
+impl Default for Monochrome {
+    fn default() -> Self {
+        Monochrome { seuil: MethodeSeuil::default(), fenetre: FENETRE_PAR_DEFAUT, reglages: Reglages::default() }
+    }
+}
+
+impl Ditherer for Monochrome {
+    fn nom(&self) -> &str {
+        "mono"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        let (couleur1, couleur2) = match palette.couleurs() {
+            [couleur1, couleur2, ..] => (*couleur1, *couleur2),
+            _ => (Rgb([255, 255, 255]), Rgb([0, 0, 0])),
+        };
+        seuillage_par_paire(img, couleur1, couleur2, self.seuil, self.fenetre, &self.reglages)
+    }
+}
+
+/// Passage à la couleur la plus proche de la palette, sans tramage.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PassageAPalette;
+
+impl Ditherer for PassageAPalette {
+    fn nom(&self) -> &str {
+        "pal"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        passage_a_une_palette(img, palette)
+    }
+}
+
+/// Tramage aléatoire en niveaux de gris, noir et blanc par défaut, la palette est ignorée.
+#[derive(Debug, Clone, Default)]
+pub struct TramageAleatoire {
+    pub bruit: Bruit,
+    /// Graine du tirage, aléatoire si absente.
+    pub graine: Option<u64>,
+    pub niveaux: NiveauxGris,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for TramageAleatoire {
+    fn nom(&self) -> &str {
+        "randTram"
+    }
+
+    fn dither(&self, img: &RgbImage, _palette: &Palette) -> RgbImage {
+        tramage_random_niveaux(img, &self.niveaux, self.bruit, self.graine, &self.reglages)
+    }
+}
+
+/// Tramage aléatoire en couleur vers la palette.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TramageAleatoirePalette {
+    pub bruit: Bruit,
+    /// Graine du tirage, aléatoire si absente.
+    pub graine: Option<u64>,
+    /// Amplitude du décalage, par défaut [`ecart_par_defaut`] de la palette.
+    pub ecart: Option<f32>,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for TramageAleatoirePalette {
+    fn nom(&self) -> &str {
+        "randTramPal"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        let ecart = self.ecart.unwrap_or_else(|| ecart_par_defaut(palette));
+        tramage_random_palette(img, palette, self.bruit, self.graine, ecart, &self.reglages)
+    }
+}
+
+/// Tramage ordonné en niveaux de gris par carte de seuils, noir et blanc par défaut, la palette est ignorée.
+#[derive(Debug, Clone, Default)]
+pub struct TramageOrdonne {
+    pub seuils: CarteSeuils,
+    pub niveaux: NiveauxGris,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for TramageOrdonne {
+    fn nom(&self) -> &str {
+        "ordered"
+    }
+
+    fn dither(&self, img: &RgbImage, _palette: &Palette) -> RgbImage {
+        ordered_dithering_niveaux(img, &self.niveaux, &self.seuils, &self.reglages)
+    }
+}
+
+/// Tramage ordonné en couleur vers la palette.
+#[derive(Debug, Clone, Default)]
+pub struct TramageOrdonnePalette {
+    pub seuils: CarteSeuils,
+    /// Amplitude du décalage, par défaut [`ecart_par_defaut`] de la palette.
+    pub ecart: Option<f32>,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for TramageOrdonnePalette {
+    fn nom(&self) -> &str {
+        "orderedPal"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        let ecart = self.ecart.unwrap_or_else(|| ecart_par_defaut(palette));
+        ordered_dithering_palette(img, palette, &self.seuils, ecart, &self.reglages)
+    }
+}
+
+/// Tramage ordonné par motif de Knoll vers la palette.
+#[derive(Debug, Clone, Default)]
+pub struct TramageMotif {
+    pub seuils: CarteSeuils,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for TramageMotif {
+    fn nom(&self) -> &str {
+        "pattern"
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        ordered_dithering_motif(img, palette, &self.seuils, &self.reglages)
+    }
+}
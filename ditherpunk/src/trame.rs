@@ -0,0 +1,153 @@
+//! Trames de demi-teintes à points groupés (clustered dot), destinées à l'impression.
+//!
+//! Contrairement à la matrice de Bayer, qui disperse les pixels allumés, ces matrices font grossir
+//! des points ou des lignes autour de centres fixes : les points isolés disparaissent moins à
+//! l'impression, où l'encre a tendance à s'étaler.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Taille maximale d'une cellule de trame.
+pub const TAILLE_MAX_TRAME: usize = 256;
+
+/// Forme des points de la trame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormeTrame {
+    /// Point qui grossit en spirale depuis le centre de la cellule.
+    #[default]
+    Spirale,
+    /// Point en losange, qui grossit selon la distance de Manhattan au centre.
+    Diagonale,
+    /// Points ronds sur une grille tournée de 45° : un point au centre de la cellule, un autre sur ses coins.
+    Point45,
+    /// Lignes horizontales, qui s'épaississent depuis le milieu de la cellule.
+    Lignes,
+}
+
+impl FromStr for FormeTrame {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "spiral" => Ok(FormeTrame::Spirale),
+            "diagonal" => Ok(FormeTrame::Diagonale),
+            "dot45" => Ok(FormeTrame::Point45),
+            "lines" => Ok(FormeTrame::Lignes),
+            _ => Err(format!("Forme de trame invalide : {} (spiral, diagonal, dot45 ou lines)", s)),
+        }
+    }
+}
+
+/// Erreur lors de la construction d'une trame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErreurTrame {
+    /// La taille de cellule est nulle ou dépasse [`TAILLE_MAX_TRAME`].
+    TailleInvalide(usize),
+}
+
+impl fmt::Display for ErreurTrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErreurTrame::TailleInvalide(taille) => {
+                write!(f, "Taille de cellule de trame invalide : {} (entre 1 et {})", taille, TAILLE_MAX_TRAME)
+            }
+        }
+    }
+}
+
+impl Error for ErreurTrame {}
+
+/// Matrice de trame à points groupés : `matrice[y][x]` est le rang, de 0 à taille² - 1,
+/// auquel la case s'allume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatriceTrame {
+    pub forme: FormeTrame,
+    pub taille: usize,
+    pub matrice: Vec<Vec<u32>>,
+}
+
+impl MatriceTrame {
+    /// Génère une trame de la forme donnée, sur une cellule `taille × taille`.
+    pub fn new(forme: FormeTrame, taille: usize) -> Result<Self, ErreurTrame> {
+        if taille == 0 || taille > TAILLE_MAX_TRAME {
+            return Err(ErreurTrame::TailleInvalide(taille));
+        }
+        let ordre = match forme {
+            FormeTrame::Spirale => ordre_spirale(taille),
+            _ => ordre_par_fonction(taille, |x, y| fonction_de_point(forme, taille, x, y)),
+        };
+
+        let mut matrice = vec![vec![0; taille]; taille];
+        for (rang, (x, y)) in ordre.into_iter().enumerate() {
+            matrice[y][x] = rang as u32;
+        }
+        Ok(MatriceTrame { forme, taille, matrice })
+    }
+}
+
+/// Cases de la cellule dans l'ordre d'une spirale partant du centre.
+fn ordre_spirale(taille: usize) -> Vec<(usize, usize)> {
+    let centre = (taille as i32 - 1) / 2;
+    let (mut x, mut y) = (centre, centre);
+    let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+    let mut ordre = vec![(x as usize, y as usize)];
+    let mut pas = 1;
+    let mut direction = 0;
+    while ordre.len() < taille * taille {
+        // Chaque longueur de côté sert deux fois : droite puis bas, gauche puis haut...
+        for _ in 0..2 {
+            let (dx, dy) = directions[direction % 4];
+            for _ in 0..pas {
+                x += dx;
+                y += dy;
+                if (0..taille as i32).contains(&x) && (0..taille as i32).contains(&y) {
+                    ordre.push((x as usize, y as usize));
+                }
+            }
+            direction += 1;
+        }
+        pas += 1;
+    }
+    ordre
+}
+
+/// Valeur de la fonction de point (spot function) au centre de la case `(x, y)`, puis critère qui départage
+/// les cases à égalité : l'angle autour du centre de point le plus proche, ou l'abscisse pour les lignes.
+fn fonction_de_point(forme: FormeTrame, taille: usize, x: usize, y: usize) -> (f32, f32) {
+    let milieu = taille as f32 / 2.0;
+    let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+    let (dx, dy) = match forme {
+        FormeTrame::Point45 => {
+            // Centre de la cellule ou coin le plus proche.
+            let coin = |p: f32| if p < milieu { 0.0 } else { taille as f32 };
+            let vers_coin = (px - coin(px), py - coin(py));
+            let vers_centre = (px - milieu, py - milieu);
+            let carre = |(a, b): (f32, f32)| a * a + b * b;
+            if carre(vers_centre) <= carre(vers_coin) {
+                vers_centre
+            } else {
+                vers_coin
+            }
+        }
+        _ => (px - milieu, py - milieu),
+    };
+    let valeur = match forme {
+        FormeTrame::Diagonale => dx.abs() + dy.abs(),
+        FormeTrame::Lignes => dy.abs(),
+        _ => dx * dx + dy * dy,
+    };
+    let angle = match forme {
+        FormeTrame::Lignes => px,
+        _ => dy.atan2(dx),
+    };
+    (valeur, angle)
+}
+
+/// Cases de la cellule triées par valeur croissante de `fonction`, puis par son critère secondaire.
+fn ordre_par_fonction(taille: usize, fonction: impl Fn(usize, usize) -> (f32, f32)) -> Vec<(usize, usize)> {
+    let mut cases: Vec<((f32, f32), (usize, usize))> =
+        (0..taille).flat_map(|y| (0..taille).map(move |x| (x, y))).map(|(x, y)| (fonction(x, y), (x, y))).collect();
+    cases.sort_by(|(a, _), (b, _)| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    cases.into_iter().map(|(_, case)| case).collect()
+}
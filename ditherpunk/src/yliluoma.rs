@@ -0,0 +1,359 @@
+//! Tramage positionnel de Joel Yliluoma pour palettes quelconques.
+//!
+//! Pour chaque couleur de l'image, un « plan de mélange » de couleurs de la palette est cherché,
+//! puis la carte de seuils (matrice de Bayer, bruit bleu...) choisit, pixel par pixel, la couleur du plan affichée :
+//! - algorithme 1 : mélange de deux couleurs dans une proportion quelconque,
+//! - algorithme 2 : mélange de plusieurs couleurs, construit pas à pas,
+//! - algorithme 3 : comme l'algorithme 1, avec en plus des mélanges de trois couleurs en damier 2×2.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use image::{Rgb, RgbImage};
+
+use crate::conversion::luminance;
+use crate::distance::Distance;
+use crate::ditherer::{Ditherer, Reglages};
+use crate::palette::Palette;
+use crate::seuils::CarteSeuils;
+use crate::tampon::{depuis_travail, vers_travail};
+
+/// Nombre de proportions essayées entre deux couleurs (algorithmes 1 et 3).
+const PROPORTIONS: u32 = 64;
+
+/// Nombre maximal de couleurs d'un plan de l'algorithme 2.
+const TAILLE_MAX_PLAN: usize = 64;
+
+/// Nombre maximal de couleurs mélangées par les algorithmes 1 et 3, dont les candidats sont en
+/// nombre quadratique (deux couleurs) ou cubique (damiers) en la taille de la palette.
+pub const TAILLE_MAX_CANDIDATS: usize = 16;
+
+/// Nombre de candidats départagés par CIE94 ou CIEDE2000 après un premier classement par CIE76.
+const PRESELECTION: usize = 32;
+
+/// Variante de l'algorithme de Yliluoma.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlgorithmeYliluoma {
+    #[default]
+    Un,
+    Deux,
+    Trois,
+}
+
+impl FromStr for AlgorithmeYliluoma {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(AlgorithmeYliluoma::Un),
+            "2" => Ok(AlgorithmeYliluoma::Deux),
+            "3" => Ok(AlgorithmeYliluoma::Trois),
+            _ => Err(format!("Algorithme de Yliluoma invalide : {} (1, 2 ou 3)", s)),
+        }
+    }
+}
+
+/// Plan de mélange d'une couleur.
+#[derive(Debug, Clone)]
+enum Plan {
+    /// `premiere` là où le seuil dépasse `proportion`, `seconde` ailleurs.
+    Deux { premiere: Rgb<u8>, seconde: Rgb<u8>, proportion: f32 },
+    /// Couleurs triées par luminance, indexées par le seuil.
+    Liste(Vec<Rgb<u8>>),
+    /// Damier 2×2, indexé par la parité de `(x, y)`.
+    Damier([Rgb<u8>; 4]),
+}
+
+impl Plan {
+    fn couleur(&self, x: u32, y: u32, seuil: f32) -> Rgb<u8> {
+        match self {
+            Plan::Deux { premiere, seconde, proportion } => {
+                if seuil < *proportion {
+                    *seconde
+                } else {
+                    *premiere
+                }
+            }
+            Plan::Liste(couleurs) => couleurs[((seuil * couleurs.len() as f32) as usize).min(couleurs.len() - 1)],
+            Plan::Damier(couleurs) => couleurs[((y & 1) * 2 + (x & 1)) as usize],
+        }
+    }
+}
+
+/// Écart perceptuel de Yliluoma : différence RGB pondérée, plus la différence de luminance.
+fn ecart_yliluoma(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let luma = |c: [f32; 3]| (c[0] * 299.0 + c[1] * 587.0 + c[2] * 114.0) / (255.0 * 1000.0);
+    let ecart_luma = luma(a) - luma(b);
+    let [dr, dv, db] = [0, 1, 2].map(|c| (a[c] - b[c]) / 255.0);
+    (dr * dr * 0.299 + dv * dv * 0.587 + db * db * 0.114) * 0.75 + ecart_luma * ecart_luma
+}
+
+/// Mélange candidat des algorithmes 1 et 3, indépendant de la couleur cible.
+struct Candidat {
+    /// Couleur obtenue, dans l'espace de la distance (voir [`Melangeur::coordonnees`]).
+    melange: [f32; 3],
+    /// Pénalité psychovisuelle du mélange, qui défavorise les couleurs trop éloignées.
+    penalite: f32,
+    plan: Plan,
+}
+
+/// Chercheur de plans, qui mélange les couleurs en lumière linéaire si demandé.
+///
+/// Les mélanges sont comparés avec la distance de la palette ; la distance euclidienne par défaut
+/// est remplacée par l'écart perceptuel de Yliluoma, pour lequel l'algorithme a été réglé.
+struct Melangeur<'a> {
+    palette: &'a Palette,
+    lineaire: bool,
+    /// Couleurs de la palette dans l'espace de travail.
+    travail: Vec<[f32; 3]>,
+    /// Couleurs de la palette dans l'espace de la distance.
+    coordonnees: Vec<[f32; 3]>,
+    /// Mélanges essayés par les algorithmes 1 et 3, calculés une seule fois quand la palette
+    /// ne dépasse pas [`TAILLE_MAX_CANDIDATS`] couleurs.
+    candidats: Option<Vec<Candidat>>,
+    algorithme: AlgorithmeYliluoma,
+}
+
+impl<'a> Melangeur<'a> {
+    fn new(palette: &'a Palette, algorithme: AlgorithmeYliluoma, lineaire: bool) -> Self {
+        let travail = palette.couleurs().iter().map(|couleur| couleur.0.map(|c| vers_travail(c, lineaire))).collect();
+        let mut melangeur =
+            Melangeur { palette, lineaire, travail, coordonnees: Vec::new(), candidats: None, algorithme };
+        melangeur.coordonnees =
+            palette.couleurs().iter().map(|couleur| melangeur.coordonnees(Self::srgb(couleur))).collect();
+        if algorithme != AlgorithmeYliluoma::Deux && palette.len() <= TAILLE_MAX_CANDIDATS {
+            let indices: Vec<usize> = (0..palette.len()).collect();
+            melangeur.candidats = Some(melangeur.candidats(&indices));
+        }
+        melangeur
+    }
+
+    fn srgb(couleur: &Rgb<u8>) -> [f32; 3] {
+        couleur.0.map(f32::from)
+    }
+
+    /// Coordonnées d'une couleur sRGB dans l'espace où la distance de la palette les compare.
+    fn coordonnees(&self, srgb: [f32; 3]) -> [f32; 3] {
+        match self.palette.distance() {
+            Distance::Euclidienne => srgb,
+            distance => distance.coordonnees(&Rgb(srgb.map(|c| c.round() as u8))),
+        }
+    }
+
+    /// Écart entre deux couleurs données par [`Melangeur::coordonnees`], au carré comme celui de Yliluoma.
+    fn comparer(&self, a: [f32; 3], b: [f32; 3]) -> f32 {
+        match self.palette.distance() {
+            Distance::Euclidienne => ecart_yliluoma(a, b),
+            distance => distance.ecart(&a, &b).powi(2),
+        }
+    }
+
+    /// Mélange pondéré des couleurs d'indices donnés, ramené en sRGB pour la comparaison.
+    fn melanger(&self, couleurs: &[(usize, f32)]) -> [f32; 3] {
+        let total: f32 = couleurs.iter().map(|(_, poids)| poids).sum();
+        [0, 1, 2].map(|c| {
+            let somme: f32 = couleurs.iter().map(|&(indice, poids)| self.travail[indice][c] * poids).sum();
+            depuis_travail(somme / total, self.lineaire) as f32
+        })
+    }
+
+    /// Candidats de l'algorithme construits sur les couleurs d'`indices`.
+    fn candidats(&self, indices: &[usize]) -> Vec<Candidat> {
+        let mut candidats = self.candidats_deux(indices);
+        if self.algorithme == AlgorithmeYliluoma::Trois {
+            candidats.extend(self.candidats_damier(indices));
+        }
+        candidats
+    }
+
+    /// Mélanges de deux couleurs (algorithme 1), la pénalité augmentant avec l'écart entre
+    /// les deux couleurs, et plus encore pour les proportions proches de 50 %.
+    fn candidats_deux(&self, indices: &[usize]) -> Vec<Candidat> {
+        let couleurs = self.palette.couleurs();
+        let mut candidats = Vec::new();
+        for (rang, &i) in indices.iter().enumerate() {
+            for &j in &indices[rang..] {
+                let (premiere, seconde) = (couleurs[i], couleurs[j]);
+                let ecart = self.comparer(self.coordonnees[i], self.coordonnees[j]);
+                let proportions = if premiere == seconde { 1 } else { PROPORTIONS };
+                for n in 0..proportions {
+                    let proportion = n as f32 / PROPORTIONS as f32;
+                    candidats.push(Candidat {
+                        melange: self.coordonnees(self.melanger(&[(i, 1.0 - proportion), (j, proportion)])),
+                        penalite: ecart * 0.1 * ((proportion - 0.5).abs() + 0.5),
+                        plan: Plan::Deux { premiere, seconde, proportion },
+                    });
+                }
+            }
+        }
+        candidats
+    }
+
+    /// Damiers de l'algorithme 3 : une troisième couleur occupe la moitié des cases et les deux
+    /// autres un quart chacune.
+    fn candidats_damier(&self, indices: &[usize]) -> Vec<Candidat> {
+        let couleurs = self.palette.couleurs();
+        let mut candidats = Vec::new();
+        for (rang, &i) in indices.iter().enumerate() {
+            for &j in &indices[rang + 1..] {
+                let (premiere, seconde) = (couleurs[i], couleurs[j]);
+                let ecart = self.comparer(self.coordonnees[i], self.coordonnees[j]);
+                let moyenne = self.coordonnees(self.melanger(&[(i, 1.0), (j, 1.0)]));
+                for &k in indices {
+                    let troisieme = couleurs[k];
+                    if troisieme == premiere || troisieme == seconde {
+                        continue;
+                    }
+                    candidats.push(Candidat {
+                        melange: self.coordonnees(self.melanger(&[(i, 1.0), (j, 1.0), (k, 2.0)])),
+                        penalite: ecart * 0.025 + self.comparer(moyenne, self.coordonnees[k]) * 0.025,
+                        plan: Plan::Damier([troisieme, premiere, seconde, troisieme]),
+                    });
+                }
+            }
+        }
+        candidats
+    }
+
+    /// Candidat le plus proche de la cible (algorithmes 1 et 3).
+    ///
+    /// Au-delà de [`TAILLE_MAX_CANDIDATS`] couleurs, les candidats sont construits pour chaque cible
+    /// sur ses [`TAILLE_MAX_CANDIDATS`] couleurs les plus proches seulement.
+    fn plan_candidat(&self, cible: [f32; 3]) -> Plan {
+        let cible = self.coordonnees(cible);
+        let proches;
+        let candidats = match &self.candidats {
+            Some(candidats) => candidats,
+            None => {
+                let mut indices: Vec<usize> = (0..self.palette.len()).collect();
+                indices.sort_by(|&a, &b| {
+                    self.comparer(cible, self.coordonnees[a]).total_cmp(&self.comparer(cible, self.coordonnees[b]))
+                });
+                indices.truncate(TAILLE_MAX_CANDIDATS);
+                proches = self.candidats(&indices);
+                &proches
+            }
+        };
+        let indice = self.plus_proche(cible, candidats.iter().map(|candidat| (candidat.melange, candidat.penalite)));
+        indice.map(|indice| candidats[indice].plan.clone()).unwrap_or(Plan::Liste(vec![Rgb([0, 0, 0])]))
+    }
+
+    /// Indice du mélange, donné par ses coordonnées et sa pénalité, le plus proche de `cible`.
+    ///
+    /// CIE94 et CIEDE2000 étant coûteuses, les mélanges sont d'abord classés par CIE76, sur les mêmes
+    /// coordonnées CIELAB, et seuls les [`PRESELECTION`] premiers sont départagés exactement.
+    fn plus_proche(&self, cible: [f32; 3], melanges: impl Iterator<Item = ([f32; 3], f32)>) -> Option<usize> {
+        let exacte = !matches!(self.palette.distance(), Distance::DeltaE94 | Distance::Ciede2000);
+        let cout = |(melange, penalite): ([f32; 3], f32), exacte: bool| {
+            let ecart = if exacte {
+                self.comparer(cible, melange)
+            } else {
+                Distance::DeltaE76.ecart(&cible, &melange).powi(2)
+            };
+            ecart + penalite
+        };
+        let mut couts: Vec<(f32, usize, ([f32; 3], f32))> =
+            melanges.enumerate().map(|(indice, melange)| (cout(melange, exacte), indice, melange)).collect();
+        if !exacte {
+            if couts.len() > PRESELECTION {
+                couts.select_nth_unstable_by(PRESELECTION, |a, b| a.0.total_cmp(&b.0));
+                couts.truncate(PRESELECTION);
+            }
+            for (cout_exact, _, melange) in &mut couts {
+                *cout_exact = cout(*melange, true);
+            }
+        }
+        couts.into_iter().min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))).map(|(_, indice, _)| indice)
+    }
+
+    /// Plan de l'algorithme 2 : à chaque étape, la couleur (et le nombre de fois, puissance de deux)
+    /// qui rapproche le plus le mélange de la cible est ajoutée, jusqu'à `taille` couleurs.
+    fn plan_liste(&self, cible: [f32; 3], taille: usize) -> Plan {
+        let cible = self.coordonnees(cible);
+        let mut plan: Vec<Rgb<u8>> = Vec::with_capacity(taille);
+        let mut somme = [0.0f32; 3];
+        while plan.len() < taille {
+            let mut essais = Vec::new();
+            let essais_max = plan.len().max(1);
+            for (indice, travail) in self.travail.iter().enumerate() {
+                let mut quantite = 1;
+                while quantite <= essais_max {
+                    let total = (plan.len() + quantite) as f32;
+                    let essai = [0, 1, 2].map(|c| {
+                        depuis_travail((somme[c] + travail[c] * quantite as f32) / total, self.lineaire) as f32
+                    });
+                    essais.push((indice, quantite, self.coordonnees(essai)));
+                    quantite *= 2;
+                }
+            }
+
+            let choix = self.plus_proche(cible, essais.iter().map(|&(_, _, essai)| (essai, 0.0))).unwrap_or(0);
+            let (indice, quantite, _) = essais[choix];
+            let quantite = quantite.min(taille - plan.len());
+            for (somme, travail) in somme.iter_mut().zip(self.travail[indice]) {
+                *somme += travail * quantite as f32;
+            }
+            plan.extend(std::iter::repeat_n(self.palette.couleurs()[indice], quantite));
+        }
+        plan.sort_by(|a, b| luminance(a, false).total_cmp(&luminance(b, false)));
+        Plan::Liste(plan)
+    }
+}
+
+/// Tramage de Yliluoma vers la palette, la carte `seuils` choisissant la couleur du plan.
+///
+/// Les plans sont calculés une seule fois par couleur distincte de l'image. Avec `reglages.lineaire`,
+/// les couleurs sont mélangées en lumière linéaire. Les mélanges sont comparés avec la distance de
+/// la palette, sauf la distance euclidienne par défaut, remplacée par l'écart perceptuel de Yliluoma.
+///
+/// Les algorithmes 1 et 3 ne mélangent, pour chaque couleur, que ses [`TAILLE_MAX_CANDIDATS`]
+/// couleurs les plus proches dans la palette.
+pub fn tramage_yliluoma(
+    img: &RgbImage,
+    palette: &Palette,
+    seuils: &CarteSeuils,
+    algorithme: AlgorithmeYliluoma,
+    reglages: &Reglages,
+) -> RgbImage {
+    if palette.is_empty() {
+        return img.clone();
+    }
+    let melangeur = Melangeur::new(palette, algorithme, reglages.lineaire);
+    let taille_plan = seuils.niveaux.min(TAILLE_MAX_PLAN);
+    let mut plans: HashMap<Rgb<u8>, Plan> = HashMap::new();
+
+    let mut img = img.clone();
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let plan = plans.entry(*pixel).or_insert_with(|| {
+            let cible = Melangeur::srgb(pixel);
+            match algorithme {
+                AlgorithmeYliluoma::Deux => melangeur.plan_liste(cible, taille_plan),
+                AlgorithmeYliluoma::Un | AlgorithmeYliluoma::Trois => melangeur.plan_candidat(cible),
+            }
+        });
+        *pixel = plan.couleur(x, y, seuils.seuil(x, y));
+    }
+    img
+}
+
+/// Tramage de Yliluoma vers la palette.
+#[derive(Debug, Clone, Default)]
+pub struct Yliluoma {
+    pub algorithme: AlgorithmeYliluoma,
+    pub seuils: CarteSeuils,
+    pub reglages: Reglages,
+}
+
+impl Ditherer for Yliluoma {
+    fn nom(&self) -> &str {
+        match self.algorithme {
+            AlgorithmeYliluoma::Un => "yliluoma1",
+            AlgorithmeYliluoma::Deux => "yliluoma2",
+            AlgorithmeYliluoma::Trois => "yliluoma3",
+        }
+    }
+
+    fn dither(&self, img: &RgbImage, palette: &Palette) -> RgbImage {
+        tramage_yliluoma(img, palette, &self.seuils, self.algorithme, &self.reglages)
+    }
+}
@@ -0,0 +1,60 @@
+//! Vérifie le tramage aléatoire : reproductibilité avec une graine et respect des tons.
+
+use ditherpunk::{tramage_random, tramage_random_palette, Bruit, Palette, Reglages};
+use image::{Rgb, RgbImage};
+
+const BRUITS: [Bruit; 4] = [Bruit::Uniforme, Bruit::Triangulaire, Bruit::Gaussien, Bruit::GradientEntrelace];
+
+fn degrade() -> RgbImage {
+    RgbImage::from_fn(64, 48, |x, y| Rgb([(x * 4) as u8, (y * 5) as u8, 128]))
+}
+
+#[test]
+fn une_graine_rend_le_tramage_reproductible() {
+    let img = degrade();
+    let palette: Palette = "black,white,red,#0080ff".parse().unwrap();
+    for bruit in BRUITS {
+        let reglages = Reglages::default();
+        assert_eq!(tramage_random(&img, bruit, Some(7), &reglages), tramage_random(&img, bruit, Some(7), &reglages));
+        assert_ne!(tramage_random(&img, bruit, Some(7), &reglages), tramage_random(&img, bruit, Some(8), &reglages));
+        assert_eq!(
+            tramage_random_palette(&img, &palette, bruit, Some(7), 64.0, &reglages),
+            tramage_random_palette(&img, &palette, bruit, Some(7), 64.0, &reglages),
+        );
+    }
+}
+
+#[test]
+fn la_proportion_de_blanc_suit_le_gris() {
+    for gris in [32u8, 128, 200] {
+        let img = RgbImage::from_pixel(64, 64, Rgb([gris, gris, gris]));
+        for bruit in [Bruit::Uniforme, Bruit::GradientEntrelace] {
+            let tramee = tramage_random(&img, bruit, Some(3), &Reglages::default());
+            let proportion = tramee.pixels().filter(|pixel| pixel[0] == 255).count() as f32 / (64.0 * 64.0);
+            assert!((proportion - gris as f32 / 255.0).abs() < 0.03, "{:?} {} : {}", bruit, gris, proportion);
+        }
+    }
+}
+
+#[test]
+fn le_tramage_en_couleur_n_utilise_que_la_palette() {
+    let palette: Palette = "black,white,red,#0080ff".parse().unwrap();
+    for bruit in BRUITS {
+        let tramee = tramage_random_palette(&degrade(), &palette, bruit, Some(1), 96.0, &Reglages::default());
+        assert!(tramee.pixels().all(|pixel| palette.couleurs().contains(pixel)));
+    }
+}
+
+#[test]
+fn une_graine_donne_toujours_le_meme_tirage() {
+    // Valeurs figées : elles ne doivent changer ni avec la version de rand, ni avec la plateforme.
+    let img = RgbImage::from_pixel(64, 64, Rgb([128, 128, 128]));
+    let resultat = tramage_random(&img, Bruit::Uniforme, Some(42), &Reglages::default());
+    let lignes: Vec<u64> = (0..4)
+        .map(|y| (0..64).filter(|&x| resultat.get_pixel(x, y)[0] == 255).fold(0, |masque, x| masque | 1 << x))
+        .collect();
+    assert_eq!(
+        lignes,
+        vec![0x47fa_0650_33fb_7859, 0xd793_f448_f9f9_ea1a, 0xb9a0_b427_56b8_a104, 0xa551_bf82_4723_3505]
+    );
+}
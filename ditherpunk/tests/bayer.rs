@@ -0,0 +1,75 @@
+//! Vérifie le contenu des matrices de Bayer et leurs seuils centrés.
+
+use ditherpunk::{ErreurBayer, MatriceBayer, TAILLE_MAX_BAYER};
+
+fn est_une_permutation(matrice: &MatriceBayer) -> bool {
+    let n = matrice.taille;
+    let mut valeurs: Vec<u32> = matrice.matrice.iter().flatten().copied().collect();
+    valeurs.sort_unstable();
+    matrice.matrice.len() == n
+        && matrice.matrice.iter().all(|ligne| ligne.len() == n)
+        && valeurs.iter().enumerate().all(|(i, &v)| v == i as u32)
+}
+
+#[test]
+fn les_matrices_classiques_sont_celles_de_bayer() {
+    assert_eq!(MatriceBayer::new_bayer_matrix(1).unwrap().matrice, vec![vec![0, 2], vec![3, 1]]);
+    assert_eq!(
+        MatriceBayer::new_bayer_matrix(2).unwrap().matrice,
+        vec![vec![0, 8, 2, 10], vec![12, 4, 14, 6], vec![3, 11, 1, 9], vec![15, 7, 13, 5]]
+    );
+    assert_eq!(MatriceBayer::avec_taille(3).unwrap().matrice, vec![vec![0, 7, 3], vec![6, 5, 2], vec![4, 1, 8]]);
+    assert_eq!(MatriceBayer::avec_taille(16).unwrap(), MatriceBayer::new_bayer_matrix(4).unwrap());
+}
+
+#[test]
+fn toute_taille_donne_une_permutation() {
+    for taille in (1..=40).chain([63, 100]) {
+        let matrice = MatriceBayer::avec_taille(taille).unwrap();
+        assert_eq!(matrice.taille, taille);
+        assert!(est_une_permutation(&matrice), "taille {}", taille);
+    }
+    for ordre in 0..=6 {
+        assert!(est_une_permutation(&MatriceBayer::new_bayer_matrix(ordre).unwrap()), "ordre {}", ordre);
+    }
+}
+
+#[test]
+fn les_seuils_sont_centres() {
+    for taille in [2, 3, 5, 7, 8, 10, 12] {
+        let matrice = MatriceBayer::avec_taille(taille).unwrap();
+        let n = taille as u32;
+        let seuils: Vec<f32> =
+            (0..n).flat_map(|y| (0..n).map(move |x| (x, y))).map(|(x, y)| matrice.seuil(x, y)).collect();
+        let moyenne = seuils.iter().sum::<f32>() / seuils.len() as f32;
+        assert!((moyenne - 0.5).abs() < 1e-5, "taille {} : moyenne {}", taille, moyenne);
+        let demi_pas = 0.5 / (taille * taille) as f32;
+        let min = seuils.iter().copied().fold(f32::MAX, f32::min);
+        let max = seuils.iter().copied().fold(f32::MIN, f32::max);
+        assert!((min - demi_pas).abs() < 1e-6 && (max - (1.0 - demi_pas)).abs() < 1e-6, "taille {}", taille);
+        assert_eq!(matrice.seuil(n, 2 * n), matrice.seuil(0, 0));
+    }
+}
+
+#[test]
+fn les_premiers_points_d_une_taille_impaire_sont_disperses() {
+    // Les deux premiers points d'une matrice 7×7 sont aussi éloignés que possible sur le tore.
+    let matrice = MatriceBayer::avec_taille(7).unwrap();
+    let position = |valeur: u32| {
+        let case = matrice.matrice.iter().flatten().position(|&v| v == valeur).unwrap();
+        ((case % 7) as i32, (case / 7) as i32)
+    };
+    let ((x0, y0), (x1, y1)) = (position(0), position(1));
+    let ecart = |a: i32, b: i32| (a - b).abs().min(7 - (a - b).abs());
+    assert_eq!((ecart(x0, x1), ecart(y0, y1)), (3, 3));
+}
+
+#[test]
+fn les_tailles_hors_limites_sont_refusees() {
+    assert_eq!(MatriceBayer::avec_taille(0), Err(ErreurBayer::TailleInvalide(0)));
+    assert_eq!(
+        MatriceBayer::avec_taille(TAILLE_MAX_BAYER + 1),
+        Err(ErreurBayer::TailleInvalide(TAILLE_MAX_BAYER + 1))
+    );
+    assert_eq!(MatriceBayer::new_bayer_matrix(9), Err(ErreurBayer::OrdreTropGrand(9)));
+}
@@ -0,0 +1,25 @@
+//! Vérifie la lecture et l'écriture des couleurs.
+
+use ditherpunk::{rgb8_to_string, string_to_rgb8};
+use image::Rgb;
+
+#[test]
+fn les_noms_suivent_les_valeurs_css_sauf_green() {
+    assert_eq!(string_to_rgb8("green").unwrap(), Rgb([0, 255, 0]));
+    assert_eq!(string_to_rgb8("webgreen").unwrap(), Rgb([0, 128, 0]));
+    assert_eq!(string_to_rgb8("lime").unwrap(), Rgb([0, 255, 0]));
+    assert_eq!(string_to_rgb8("x11green").unwrap(), Rgb([0, 255, 0]));
+    assert_eq!(string_to_rgb8("gray").unwrap(), Rgb([128, 128, 128]));
+    assert_eq!(string_to_rgb8("maroon").unwrap(), Rgb([128, 0, 0]));
+    assert_eq!(string_to_rgb8("purple").unwrap(), Rgb([128, 0, 128]));
+    assert_eq!(string_to_rgb8("Light Sea-Green").unwrap(), Rgb([32, 178, 170]));
+}
+
+#[test]
+fn un_nom_ecrit_se_relit_a_l_identique() {
+    for couleur in [Rgb([0, 128, 0]), Rgb([0, 255, 0]), Rgb([255, 0, 0]), Rgb([160, 82, 45]), Rgb([1, 2, 3])] {
+        assert_eq!(string_to_rgb8(&rgb8_to_string(couleur)).unwrap(), couleur, "{}", rgb8_to_string(couleur));
+    }
+    assert_eq!(rgb8_to_string(Rgb([0, 255, 0])), "green");
+    assert_eq!(rgb8_to_string(Rgb([0, 128, 0])), "webgreen");
+}
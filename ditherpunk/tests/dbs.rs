@@ -0,0 +1,64 @@
+//! Vérifie la convergence de la recherche binaire directe.
+
+use ditherpunk::{
+    recherche_binaire_directe, Ditherer, Monochrome, Palette, RechercheBinaireDirecte, Reglages, Registre,
+    TramageAleatoire,
+};
+use image::{Rgb, RgbImage};
+
+fn degrade() -> RgbImage {
+    RgbImage::from_fn(48, 32, |x, _| {
+        let gris = (x * 255 / 47) as u8;
+        Rgb([gris, gris, gris])
+    })
+}
+
+#[test]
+fn l_erreur_percue_ne_fait_que_baisser() {
+    let img = degrade();
+    let depart = TramageAleatoire { graine: Some(1), ..Default::default() }.dither(&img, &Palette::noir_et_blanc());
+    let (_, rapport) = recherche_binaire_directe(&img, &depart, 20, &Reglages::default());
+
+    let mut precedente = rapport.erreur_initiale;
+    for passe in &rapport.passes {
+        assert!(passe.erreur <= precedente + 1e-9, "{} > {}", passe.erreur, precedente);
+        precedente = passe.erreur;
+    }
+    assert!(precedente < rapport.erreur_initiale / 2.0);
+}
+
+#[test]
+fn la_recherche_s_arrete_quand_plus_rien_ne_change() {
+    let img = degrade();
+    let depart = TramageAleatoire { graine: Some(1), ..Default::default() }.dither(&img, &Palette::noir_et_blanc());
+    let (resultat, rapport) = recherche_binaire_directe(&img, &depart, 1000, &Reglages::default());
+    let derniere = rapport.passes.last().unwrap();
+    assert!(rapport.passes.len() < 1000);
+    assert_eq!(derniere.basculements + derniere.echanges, 0);
+
+    // Repartir du résultat ne change plus rien.
+    let (encore, rapport) = recherche_binaire_directe(&img, &resultat, 10, &Reglages::default());
+    assert_eq!(encore, resultat);
+    assert_eq!(rapport.passes.len(), 1);
+}
+
+#[test]
+fn un_gris_moyen_donne_moitie_de_pixels_blancs() {
+    let img = RgbImage::from_pixel(32, 32, Rgb([128, 128, 128]));
+    let resultat = Registre::par_defaut().get("dbs").unwrap().dither(&img, &Palette::noir_et_blanc());
+    let blancs = resultat.pixels().filter(|pixel| pixel[0] == 255).count();
+    assert!((480..=544).contains(&blancs), "{} pixels blancs", blancs);
+}
+
+#[test]
+fn sans_iteration_le_depart_mono_n_est_pas_inverse() {
+    let img = degrade();
+    let mono = Monochrome::default().dither(&img, &Palette::new(vec![]));
+    let dbs = RechercheBinaireDirecte {
+        depart: Box::new(Monochrome::default()),
+        iterations: 0,
+        reglages: Reglages::default(),
+    };
+    assert_eq!(dbs.dither(&img, &Palette::noir_et_blanc()), mono);
+    assert_eq!(mono.get_pixel(47, 0), &Rgb([255, 255, 255]));
+}
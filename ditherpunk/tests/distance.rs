@@ -0,0 +1,122 @@
+//! Vérifie les distances entre couleurs.
+
+use ditherpunk::Distance;
+use image::Rgb;
+
+/// Paires de test de CIEDE2000 publiées par Sharma, Wu et Dalal (2005) : L*a*b* des deux couleurs
+/// et écart attendu, arrondi à quatre décimales.
+const SHARMA: [([f32; 3], [f32; 3], f32); 34] = [
+    ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+    ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+    ([50.0, 2.8361, -74.0200], [50.0, 0.0, -82.7485], 3.4412),
+    ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, -1.1848, -84.8006], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, -0.9009, -85.5211], [50.0, 0.0, -82.7485], 1.0000),
+    ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+    ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0009], 7.1792),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0010], 7.1792),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0011], 7.2195),
+    ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0012], 7.2195),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0009, -2.4900], 4.8045),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0010, -2.4900], 4.8045),
+    ([50.0, -0.0010, 2.4900], [50.0, 0.0011, -2.4900], 4.7461),
+    ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+    ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+    ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+    ([50.0, 2.5, 0.0], [56.0, -27.0, -3.0], 31.9030),
+    ([50.0, 2.5, 0.0], [58.0, 24.0, 15.0], 19.4535),
+    ([50.0, 2.5, 0.0], [50.0, 3.1736, 0.5854], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 3.2972, 0.0], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 1.8634, 0.5757], 1.0000),
+    ([50.0, 2.5, 0.0], [50.0, 3.2592, 0.3350], 1.0000),
+    ([60.2574, -34.0099, 36.2677], [60.4626, -34.1751, 39.4387], 1.2644),
+    ([63.0109, -31.0961, -5.8663], [62.8187, -29.7946, -4.0864], 1.2630),
+    ([61.2901, 3.7196, -5.3901], [61.4292, 2.2480, -4.9620], 1.8731),
+    ([35.0831, -44.1164, 3.7933], [35.0232, -40.0716, 1.5901], 1.8645),
+    ([22.7233, 20.0904, -46.6940], [23.0331, 14.9730, -42.5619], 2.0373),
+    ([36.4612, 47.8580, 18.3852], [36.2715, 50.5065, 21.2231], 1.4146),
+    ([90.8027, -2.0831, 1.4410], [91.1528, -1.6435, 0.0447], 1.4441),
+    ([90.9257, -0.5406, -0.9208], [88.6381, -0.8985, -0.7239], 1.5381),
+    ([6.7747, -0.2908, -2.4247], [5.8714, -0.0985, -2.2286], 0.6377),
+    ([2.0776, 0.0795, -1.1350], [0.9033, -0.0636, -0.5514], 0.9082),
+];
+
+fn proche(a: [f32; 3], b: [f32; 3], tolerance: f32) -> bool {
+    (0..3).all(|c| (a[c] - b[c]).abs() <= tolerance)
+}
+
+#[test]
+fn ciede2000_reproduit_les_paires_de_sharma() {
+    for (numero, (lab1, lab2, attendu)) in SHARMA.iter().enumerate() {
+        let ecart = Distance::Ciede2000.ecart(lab1, lab2);
+        assert!((ecart - attendu).abs() < 1e-3, "paire {} : {} au lieu de {}", numero + 1, ecart, attendu);
+        let inverse = Distance::Ciede2000.ecart(lab2, lab1);
+        assert!((inverse - attendu).abs() < 1e-3, "paire {} inversée : {}", numero + 1, inverse);
+    }
+}
+
+#[test]
+fn cie76_et_cie94_mesurent_l_ecart_dans_cielab() {
+    let (reference, echantillon) = ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0]);
+    assert!((Distance::DeltaE76.ecart(&reference, &echantillon) - 36.8680).abs() < 1e-3);
+    // CIE94 pondère la chroma et la teinte par celles de la première couleur, la référence.
+    assert!((Distance::DeltaE94.ecart(&reference, &echantillon) - 34.6892).abs() < 1e-3);
+    assert!((Distance::DeltaE94.ecart(&echantillon, &reference) - 26.1398).abs() < 1e-3);
+
+    let (reference, echantillon) = ([60.2574, -34.0099, 36.2677], [60.4626, -34.1751, 39.4387]);
+    assert!((Distance::DeltaE76.ecart(&reference, &echantillon) - 3.1819).abs() < 1e-3);
+    assert!((Distance::DeltaE94.ecart(&reference, &echantillon) - 1.3910).abs() < 1e-3);
+
+    for distance in [Distance::DeltaE76, Distance::DeltaE94, Distance::Ciede2000] {
+        assert!(proche(distance.coordonnees(&Rgb([255, 255, 255])), [100.0, 0.0, 0.0], 0.05), "{:?}", distance);
+        assert!(proche(distance.coordonnees(&Rgb([255, 0, 0])), [53.24, 80.09, 67.20], 0.05), "{:?}", distance);
+        assert!(proche(distance.coordonnees(&Rgb([0, 0, 0])), [0.0, 0.0, 0.0], 1e-3), "{:?}", distance);
+    }
+}
+
+#[test]
+fn redmean_pondere_les_composantes_selon_le_rouge() {
+    let (noir, blanc) = (Rgb([0, 0, 0]), Rgb([255, 255, 255]));
+    assert!((Distance::Redmean.distance(&noir, &blanc) - 764.83).abs() < 1e-2);
+    assert!((Distance::Redmean.distance(&noir, &Rgb([255, 0, 0])) - 403.03).abs() < 1e-2);
+    assert!((Distance::Redmean.distance(&noir, &Rgb([0, 0, 255])) - 441.39).abs() < 1e-2);
+    // Le vert pèse deux fois plus que le rouge ou le bleu.
+    assert_eq!(Distance::Redmean.distance(&noir, &Rgb([0, 255, 0])), 510.0);
+    assert_eq!(Distance::Redmean.distance(&blanc, &blanc), 0.0);
+}
+
+#[test]
+fn oklab_suit_les_valeurs_de_reference() {
+    let references = [
+        (Rgb([255, 255, 255]), [1.0, 0.0, 0.0]),
+        (Rgb([0, 0, 0]), [0.0, 0.0, 0.0]),
+        (Rgb([255, 0, 0]), [0.62796, 0.22486, 0.12585]),
+        (Rgb([0, 255, 0]), [0.86644, -0.23389, 0.17950]),
+        (Rgb([0, 0, 255]), [0.45201, -0.03246, -0.31153]),
+    ];
+    for (couleur, oklab) in references {
+        assert!(proche(Distance::Oklab.coordonnees(&couleur), oklab, 1e-3), "{:?}", couleur);
+    }
+    assert!((Distance::Oklab.distance(&Rgb([0, 0, 0]), &Rgb([255, 255, 255])) - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn chaque_distance_est_nulle_entre_une_couleur_et_elle_meme() {
+    let distances = [
+        Distance::Euclidienne,
+        Distance::Redmean,
+        Distance::DeltaE76,
+        Distance::DeltaE94,
+        Distance::Ciede2000,
+        Distance::Oklab,
+    ];
+    let couleurs =
+        [Rgb([0, 0, 0]), Rgb([255, 255, 255]), Rgb([200, 30, 40]), Rgb([20, 120, 220]), Rgb([128, 128, 128])];
+    for distance in distances {
+        for couleur in &couleurs {
+            assert!(distance.distance(couleur, couleur).abs() < 1e-4, "{:?}, {:?}", distance, couleur);
+        }
+        assert!(distance.distance(&couleurs[0], &couleurs[1]) > distance.distance(&couleurs[4], &couleurs[1]));
+    }
+}
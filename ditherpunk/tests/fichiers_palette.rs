@@ -0,0 +1,105 @@
+//! Vérifie la lecture des fichiers de palette, avec de petits fichiers écrits en ligne.
+
+use ditherpunk::fichier_palette::{lire_aco, lire_ase, lire_gpl, lire_jasc, lire_liste_hex, lire_paint_net};
+use ditherpunk::ErreurPalette;
+use image::Rgb;
+
+/// Bloc de couleur ASE nommé `nom`, au modèle `modele` et aux composantes `valeurs`.
+fn bloc_ase(nom: &str, modele: &[u8; 4], valeurs: &[f32]) -> Vec<u8> {
+    let mut contenu = Vec::new();
+    let nom: Vec<u16> = nom.encode_utf16().chain([0]).collect();
+    contenu.extend((nom.len() as u16).to_be_bytes());
+    contenu.extend(nom.iter().flat_map(|c| c.to_be_bytes()));
+    contenu.extend(modele);
+    contenu.extend(valeurs.iter().flat_map(|v| v.to_be_bytes()));
+    contenu.extend(2u16.to_be_bytes());
+
+    let mut bloc = Vec::new();
+    bloc.extend(1u16.to_be_bytes());
+    bloc.extend((contenu.len() as u32).to_be_bytes());
+    bloc.extend(contenu);
+    bloc
+}
+
+fn fichier_ase() -> Vec<u8> {
+    let mut octets = b"ASEF".to_vec();
+    octets.extend(1u16.to_be_bytes());
+    octets.extend(0u16.to_be_bytes());
+    octets.extend(4u32.to_be_bytes());
+    // Début de groupe, couleur RGB, couleur grise, fin de groupe.
+    octets.extend([0xc0, 0x01, 0, 0, 0, 4, 0, 1, 0, 0]);
+    octets.extend(bloc_ase("rouge", b"RGB ", &[1.0, 0.0, 0.0]));
+    octets.extend(bloc_ase("gris", b"Gray", &[0.5]));
+    octets.extend([0xc0, 0x02, 0, 0, 0, 0]);
+    octets
+}
+
+fn fichier_aco() -> Vec<u8> {
+    let mut octets = Vec::new();
+    for mot in [1u16, 2, 0, 0xffff, 0x8000, 0, 0, 8, 2500, 0, 0, 0] {
+        octets.extend(mot.to_be_bytes());
+    }
+    octets
+}
+
+fn est_invalide(resultat: Result<Vec<Rgb<u8>>, ErreurPalette>) -> bool {
+    matches!(resultat, Err(ErreurPalette::Invalide { .. }))
+}
+
+#[test]
+fn lit_une_palette_gimp() {
+    let texte = "GIMP Palette\nName: Essai\nColumns: 4\n# commentaire\n255   0   0\tRouge\n  0 128 255 Bleu\n";
+    assert_eq!(lire_gpl(texte).unwrap(), vec![Rgb([255, 0, 0]), Rgb([0, 128, 255])]);
+    assert!(est_invalide(lire_gpl("255 0 0\n")));
+    assert!(est_invalide(lire_gpl("GIMP Palette\n255 0\n")));
+    assert!(est_invalide(lire_gpl("GIMP Palette\n256 0 0\n")));
+}
+
+#[test]
+fn lit_une_palette_jasc() {
+    let texte = "JASC-PAL\n0100\n2\n0 0 0\n255 255 255\n";
+    assert_eq!(lire_jasc(texte).unwrap(), vec![Rgb([0, 0, 0]), Rgb([255, 255, 255])]);
+    assert!(est_invalide(lire_jasc("JASC-PAL\n0100\n3\n0 0 0\n255 255 255\n")));
+    assert!(est_invalide(lire_jasc("JASC-PAL\n0100\n")));
+}
+
+#[test]
+fn lit_une_palette_paint_net() {
+    let texte = "; paint.net Palette File\nFFFF0000\nff00ff80\n";
+    assert_eq!(lire_paint_net(texte).unwrap(), vec![Rgb([255, 0, 0]), Rgb([0, 255, 128])]);
+    assert!(est_invalide(lire_paint_net("FF0000\n")));
+    // Huit octets, mais le découpage tomberait au milieu du « é ».
+    assert!(est_invalide(lire_paint_net("é123456\n")));
+}
+
+#[test]
+fn lit_une_liste_hexadecimale() {
+    let texte = "// couleurs\n#ff0000\n00ff00\n\n;fin\n";
+    assert_eq!(lire_liste_hex(texte).unwrap(), vec![Rgb([255, 0, 0]), Rgb([0, 255, 0])]);
+    assert!(est_invalide(lire_liste_hex("ff00zz\n")));
+}
+
+#[test]
+fn lit_une_palette_ase() {
+    assert_eq!(lire_ase(&fichier_ase()).unwrap(), vec![Rgb([255, 0, 0]), Rgb([128, 128, 128])]);
+    assert!(est_invalide(lire_ase(b"ASEX\0\x01\0\0\0\0\0\0")));
+}
+
+#[test]
+fn lit_une_palette_aco() {
+    assert_eq!(lire_aco(&fichier_aco()).unwrap(), vec![Rgb([255, 128, 0]), Rgb([192, 192, 192])]);
+    let mut version = fichier_aco();
+    version[1] = 3;
+    assert!(est_invalide(lire_aco(&version)));
+}
+
+#[test]
+fn un_fichier_binaire_tronque_est_refuse() {
+    let (ase, aco) = (fichier_ase(), fichier_aco());
+    for longueur in 0..ase.len() {
+        assert!(est_invalide(lire_ase(&ase[..longueur])), "ASE tronqué à {} octets", longueur);
+    }
+    for longueur in 0..aco.len() {
+        assert!(est_invalide(lire_aco(&aco[..longueur])), "ACO tronqué à {} octets", longueur);
+    }
+}
@@ -0,0 +1,53 @@
+//! Vérifie les formules de conversion en niveaux de gris et leur prise en compte par les modes noir et blanc.
+
+use ditherpunk::{seuil_automatique, Luma, MethodeSeuil, Palette, Reglages, Registre};
+use image::{Rgb, RgbImage};
+
+#[test]
+fn les_formules_donnent_le_gris_attendu() {
+    let pixel = Rgb([255, 0, 51]);
+    let attendus = [
+        (Luma::Rec601, 0.299 * 255.0 + 0.114 * 51.0),
+        (Luma::Rec709, 0.2126 * 255.0 + 0.0722 * 51.0),
+        (Luma::Rec2020, 0.2627 * 255.0 + 0.0593 * 51.0),
+        (Luma::Moyenne, 102.0),
+        (Luma::LuminositeTsl, 127.5),
+        (Luma::ValeurTsv, 255.0),
+        (Luma::Rouge, 255.0),
+        (Luma::Vert, 0.0),
+        (Luma::Bleu, 51.0),
+    ];
+    for (luma, attendu) in attendus {
+        assert!((luma.gris(&pixel, false) - attendu).abs() < 0.01, "{:?}", luma);
+    }
+    // Le gris sRGB 119 a une clarté L* d'environ 50.
+    assert!((Luma::ClarteCie.gris(&Rgb([119, 119, 119]), false) - 127.5).abs() < 1.0);
+    assert!((Luma::ClarteCie.gris(&Rgb([255, 255, 255]), false) - 255.0).abs() < 0.5);
+    for luma in [Luma::Rec601, Luma::Rec709, Luma::Rec2020, Luma::Moyenne, Luma::LuminositeTsl] {
+        assert!((luma.gris(&Rgb([90, 90, 90]), false) - 90.0).abs() < 0.01, "{:?}", luma);
+    }
+}
+
+#[test]
+fn la_formule_choisie_change_le_seuillage() {
+    let img = RgbImage::from_pixel(8, 8, Rgb([255, 0, 0]));
+    // Le mode "mono" prend la première couleur au-dessus du seuil.
+    let palette = Palette::new(vec![Rgb([255, 255, 255]), Rgb([0, 0, 0])]);
+    for (luma, blanc) in [(Luma::Rouge, true), (Luma::Vert, false), (Luma::Bleu, false), (Luma::ValeurTsv, true)] {
+        let reglages = Reglages { luma, ..Default::default() };
+        let registre = Registre::avec_reglages(reglages);
+        for nom in ["mono", "ordered", "simple"] {
+            let resultat = registre.get(nom).unwrap().dither(&img, &palette);
+            let attendu = if blanc { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) };
+            assert!(resultat.pixels().all(|pixel| *pixel == attendu), "{:?} {}", luma, nom);
+        }
+    }
+}
+
+#[test]
+fn le_seuil_automatique_suit_la_formule() {
+    let img = RgbImage::from_fn(16, 16, |x, _| if x < 8 { Rgb([200, 0, 0]) } else { Rgb([0, 0, 100]) });
+    let reglages = Reglages { luma: Luma::Moyenne, ..Default::default() };
+    let seuil = seuil_automatique(&img, MethodeSeuil::Moyenne, &reglages).unwrap();
+    assert!((seuil - 50.0).abs() < 0.01, "{}", seuil);
+}
@@ -0,0 +1,65 @@
+//! Vérifie le tramage en N niveaux de gris.
+
+use ditherpunk::{
+    diffusion_d_erreur_simple_niveaux, en_gris, image_grise, ordered_dithering_niveaux, tramage_random_niveaux,
+    tramage_riemersma_niveaux, Bruit, CarteSeuils, NiveauxGris, Reglages, Registre,
+};
+use image::{Rgb, RgbImage};
+
+fn degrade() -> RgbImage {
+    RgbImage::from_fn(64, 32, |x, y| Rgb([(x * 4) as u8, (y * 8) as u8, 100]))
+}
+
+#[test]
+fn les_niveaux_se_lisent_en_nombre_ou_en_liste() {
+    assert_eq!("4".parse::<NiveauxGris>().unwrap().niveaux(), &[0, 85, 170, 255]);
+    assert_eq!("255, 0,96,176,96".parse::<NiveauxGris>().unwrap().niveaux(), &[0, 96, 176, 255]);
+    assert!("16".parse::<NiveauxGris>().unwrap().sont_uniformes());
+    assert!(!"0,96,176,255".parse::<NiveauxGris>().unwrap().sont_uniformes());
+    for invalide in ["1", "257", "0,0", "0,300", "quatre"] {
+        assert!(invalide.parse::<NiveauxGris>().is_err(), "{}", invalide);
+    }
+}
+
+#[test]
+fn la_diffusion_conserve_le_gris_moyen() {
+    let img = RgbImage::from_fn(64, 64, |x, _| Rgb([x as u8 * 4, x as u8 * 4, x as u8 * 4]));
+    let moyenne = |img: &RgbImage| img.pixels().map(|pixel| pixel[0] as f64).sum::<f64>() / (64.0 * 64.0);
+    for nombre in [2, 4, 16] {
+        let niveaux = NiveauxGris::uniformes(nombre).unwrap();
+        let resultat = diffusion_d_erreur_simple_niveaux(&img, &niveaux, &Reglages::default());
+        assert!((moyenne(&resultat) - moyenne(&img)).abs() < 1.0, "{} niveaux", nombre);
+    }
+}
+
+#[test]
+fn seuls_les_niveaux_choisis_apparaissent() {
+    let img = degrade();
+    let niveaux: NiveauxGris = "0,96,176,255".parse().unwrap();
+    let reglages = Reglages { lineaire: true, ..Default::default() };
+    let resultats = [
+        ordered_dithering_niveaux(&img, &niveaux, &CarteSeuils::default(), &reglages),
+        tramage_random_niveaux(&img, &niveaux, Bruit::Triangulaire, Some(3), &reglages),
+        diffusion_d_erreur_simple_niveaux(&img, &niveaux, &reglages),
+        tramage_riemersma_niveaux(&img, &niveaux, &reglages),
+        Registre::par_defaut().get("floyd").unwrap().dither(&en_gris(&img, &reglages), &niveaux.palette()),
+    ];
+    for resultat in resultats {
+        let grise = image_grise(&resultat);
+        assert!(grise.pixels().all(|pixel| niveaux.niveaux().contains(&pixel[0])));
+        for niveau in niveaux.niveaux() {
+            assert!(grise.pixels().any(|pixel| pixel[0] == *niveau), "niveau {} absent", niveau);
+        }
+    }
+}
+
+#[test]
+fn un_gris_entre_deux_niveaux_se_partage_entre_eux() {
+    // 113 est au tiers de l'écart entre 85 et 170 : il dépasse 21 des 64 seuils de la matrice de Bayer 8×8.
+    let img = RgbImage::from_pixel(64, 64, Rgb([113, 113, 113]));
+    let niveaux = NiveauxGris::uniformes(4).unwrap();
+    let resultat = ordered_dithering_niveaux(&img, &niveaux, &CarteSeuils::default(), &Reglages::default());
+    assert!(resultat.pixels().all(|pixel| pixel[0] == 85 || pixel[0] == 170));
+    let hauts = resultat.pixels().filter(|pixel| pixel[0] == 170).count();
+    assert_eq!(hauts, 21 * 64);
+}
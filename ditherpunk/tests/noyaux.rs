@@ -0,0 +1,176 @@
+//! Vérifie que chaque noyau de diffusion envoie l'erreur exactement aux voisins prévus,
+//! en comparant le résultat à une diffusion de référence écrite à partir des décalages publiés.
+
+use ditherpunk::{charger_noyau, lire_noyau, DiffusionMatrice, Ditherer, ErreurNoyau, FloydSteinberg, Palette};
+use image::{Rgb, RgbImage};
+
+/// Décalages `(dx, dy, poids)` des voisins, relatifs au pixel courant, et diviseur.
+type Reference = (&'static [(i32, i32, i32)], i32);
+
+const FLOYD_STEINBERG: Reference = (&[(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)], 16);
+
+const REFERENCES: &[(&str, Reference)] = &[
+    (
+        "jjn",
+        (
+            &[
+                (1, 0, 7), (2, 0, 5),
+                (-2, 1, 3), (-1, 1, 5), (0, 1, 7), (1, 1, 5), (2, 1, 3),
+                (-2, 2, 1), (-1, 2, 3), (0, 2, 5), (1, 2, 3), (2, 2, 1),
+            ],
+            48,
+        ),
+    ),
+    ("atkinson", (&[(1, 0, 1), (2, 0, 1), (-1, 1, 1), (0, 1, 1), (1, 1, 1), (0, 2, 1)], 8)),
+    (
+        "stucki",
+        (
+            &[
+                (1, 0, 8), (2, 0, 4),
+                (-2, 1, 2), (-1, 1, 4), (0, 1, 8), (1, 1, 4), (2, 1, 2),
+                (-2, 2, 1), (-1, 2, 2), (0, 2, 4), (1, 2, 2), (2, 2, 1),
+            ],
+            42,
+        ),
+    ),
+    ("burkes", (&[(1, 0, 8), (2, 0, 4), (-2, 1, 2), (-1, 1, 4), (0, 1, 8), (1, 1, 4), (2, 1, 2)], 32)),
+    (
+        "sierra3",
+        (
+            &[
+                (1, 0, 5), (2, 0, 3),
+                (-2, 1, 2), (-1, 1, 4), (0, 1, 5), (1, 1, 4), (2, 1, 2),
+                (-1, 2, 2), (0, 2, 3), (1, 2, 2),
+            ],
+            32,
+        ),
+    ),
+    ("sierra2", (&[(1, 0, 4), (2, 0, 3), (-2, 1, 1), (-1, 1, 2), (0, 1, 3), (1, 1, 2), (2, 1, 1)], 16)),
+    ("sierraLite", (&[(1, 0, 2), (-1, 1, 1), (0, 1, 1)], 4)),
+    ("fan", (&[(1, 0, 7), (-2, 1, 1), (-1, 1, 3), (0, 1, 5)], 16)),
+    ("shiauFan", (&[(1, 0, 4), (-2, 1, 1), (-1, 1, 1), (0, 1, 2)], 8)),
+    ("shiauFan2", (&[(1, 0, 8), (-3, 1, 1), (-2, 1, 1), (-1, 1, 2), (0, 1, 4)], 16)),
+    (
+        "stevensonArce",
+        (
+            &[
+                (2, 0, 32),
+                (-3, 1, 12), (-1, 1, 26), (1, 1, 30), (3, 1, 16),
+                (-2, 2, 12), (0, 2, 26), (2, 2, 12),
+                (-3, 3, 5), (-1, 3, 12), (1, 3, 12), (3, 3, 5),
+            ],
+            200,
+        ),
+    ),
+];
+
+/// Diffusion d'erreur directe, sans écrêtage ni lumière linéaire, vers les voisins donnés.
+fn diffusion_de_reference(img: &RgbImage, palette: &Palette, (voisins, diviseur): Reference) -> RgbImage {
+    let (largeur, hauteur) = img.dimensions();
+    let mut valeurs: Vec<[f32; 3]> = img.pixels().map(|p| p.0.map(f32::from)).collect();
+    let mut sortie = RgbImage::new(largeur, hauteur);
+
+    for y in 0..hauteur {
+        for x in 0..largeur {
+            let valeur = valeurs[(y * largeur + x) as usize];
+            let couleur = palette.plus_proche(&Rgb(valeur.map(|v| v.round().clamp(0.0, 255.0) as u8)));
+            let erreur = [0, 1, 2].map(|c| valeur[c] - couleur[c] as f32);
+            sortie.put_pixel(x, y, couleur);
+
+            for &(dx, dy, poids) in voisins {
+                let (vx, vy) = (x as i32 + dx, y as i32 + dy);
+                if vx < 0 || vx >= largeur as i32 || vy >= hauteur as i32 {
+                    continue;
+                }
+                let voisin = &mut valeurs[(vy as u32 * largeur + vx as u32) as usize];
+                for c in 0..3 {
+                    voisin[c] += erreur[c] * (poids as f32 / diviseur as f32);
+                }
+            }
+        }
+    }
+    sortie
+}
+
+/// Dégradé coloré assez varié pour que chaque voisin influence le résultat.
+fn image_de_test() -> RgbImage {
+    RgbImage::from_fn(37, 23, |x, y| Rgb([(x * 7) as u8, (y * 11) as u8, ((x * y) % 256) as u8]))
+}
+
+fn palettes() -> Vec<Palette> {
+    vec![
+        Palette::noir_et_blanc(),
+        "black,white,red,#0080ff".parse().unwrap(),
+    ]
+}
+
+#[test]
+fn les_noyaux_integres_suivent_les_decalages_publies() {
+    let img = image_de_test();
+    let catalogue = DiffusionMatrice::catalogue();
+    assert_eq!(catalogue.len(), REFERENCES.len());
+
+    for (nom, reference) in REFERENCES {
+        let noyau = catalogue.iter().find(|n| n.nom == *nom).unwrap_or_else(|| panic!("noyau {} absent", nom));
+        for palette in palettes() {
+            assert_eq!(noyau.dither(&img, &palette), diffusion_de_reference(&img, &palette, *reference), "noyau {}", nom);
+        }
+    }
+}
+
+#[test]
+fn floyd_steinberg_correspond_a_la_reference() {
+    let img = image_de_test();
+    for palette in palettes() {
+        let attendu = diffusion_de_reference(&img, &palette, FLOYD_STEINBERG);
+        assert_eq!(FloydSteinberg::default().dither(&img, &palette), attendu);
+    }
+}
+
+#[test]
+fn l_ancre_d_un_noyau_utilisateur_peut_etre_sur_une_autre_ligne() {
+    let img = image_de_test();
+    let descriptions = [
+        "0 * 7; 3 5 1 / 16",
+        "0 0 0; 0 * 7; 3 5 1",
+        r#"{"weights": [[0, 0, 7, 0], [3, 5, 1, 0]], "anchor": [1, 0]}"#,
+        "weights = [[0, 0, 0], [0, 0, 7], [3, 5, 1]]\nanchor = [1, 1]\ndivisor = 16",
+    ];
+
+    for description in descriptions {
+        let noyau = lire_noyau("test", description).unwrap();
+        for palette in palettes() {
+            let attendu = diffusion_de_reference(&img, &palette, FLOYD_STEINBERG);
+            assert_eq!(noyau.dither(&img, &palette), attendu, "noyau {:?}", description);
+        }
+    }
+}
+
+#[test]
+fn les_poids_precedant_l_ancre_sont_refuses() {
+    assert!(lire_noyau("test", "1 * 7; 3 5 1").is_err());
+    assert!(lire_noyau("test", "0 1 0; 0 * 7; 3 5 1").is_err());
+    assert!(lire_noyau("test", r#"{"weights": [[0, 0, 7]], "anchor": [3, 0]}"#).is_err());
+}
+
+#[test]
+fn les_listes_trop_imbriquees_sont_refusees() {
+    let profonde = format!("{{\"weights\": {}1{}}}", "[".repeat(100_000), "]".repeat(100_000));
+    assert!(matches!(lire_noyau("test", &profonde), Err(ErreurNoyau::Invalide { .. })));
+    assert!(lire_noyau("test", r#"{"weights": [[[0, 0, 7]]]}"#).is_err());
+    assert!(lire_noyau("test", &format!("weights = {}", "[".repeat(100_000))).is_err());
+}
+
+#[test]
+fn une_somme_de_poids_trop_grande_est_refusee() {
+    assert!(matches!(lire_noyau("test", "0 * 2147483647 1"), Err(ErreurNoyau::Invalide { .. })));
+    assert_eq!(lire_noyau("test", "0 * 2147483647 1 / 2").unwrap().facteur, 2);
+}
+
+#[test]
+fn un_fichier_de_noyau_absent_est_signale() {
+    for source in ["noyau_absent.json", "dossier/noyau_absent.TOML", "absent.txt"] {
+        assert!(matches!(charger_noyau(source), Err(ErreurNoyau::FichierIntrouvable(_))), "{}", source);
+    }
+    assert!(charger_noyau("0 * 7; 3 5 1 / 16").is_ok());
+}
@@ -0,0 +1,63 @@
+//! Vérifie les palettes prédéfinies.
+
+use ditherpunk::{noms_presets, preset, PRESETS};
+use image::Rgb;
+
+#[test]
+fn chaque_palette_a_son_nombre_de_couleurs() {
+    let attendus = [
+        ("gameboy", 4),
+        ("cga0", 4),
+        ("cga0-high", 4),
+        ("cga1", 4),
+        ("cga1-high", 4),
+        ("ega", 16),
+        ("c64", 16),
+        ("pico8", 16),
+        ("zxspectrum", 15),
+        ("mac2", 16),
+        ("web-safe", 216),
+    ];
+    for (nom, nombre) in attendus {
+        assert_eq!(preset(nom).unwrap_or_else(|| panic!("{} inconnue", nom)).len(), nombre, "{}", nom);
+    }
+    assert_eq!(PRESETS.len() + 2, noms_presets().len());
+    for nom in noms_presets().into_iter().filter(|&nom| nom != "grayscale-N") {
+        assert!(preset(nom).is_some(), "{}", nom);
+    }
+}
+
+#[test]
+fn les_couleurs_de_reference_sont_a_leur_place() {
+    let couleur = |nom: &str, indice: usize| preset(nom).unwrap().couleurs()[indice];
+    assert_eq!(couleur("pico8", 8), Rgb([0xFF, 0x00, 0x4D]));
+    assert_eq!(couleur("pico8", 0), Rgb([0, 0, 0]));
+    assert_eq!(couleur("gameboy", 0), Rgb([0x0F, 0x38, 0x0F]));
+    assert_eq!(couleur("ega", 6), Rgb([0xAA, 0x55, 0x00]));
+    assert_eq!(couleur("c64", 14), Rgb([0x6C, 0x5E, 0xB5]));
+    assert_eq!(couleur("zxspectrum", 14), Rgb([255, 255, 255]));
+    assert_eq!(couleur("mac2", 0), Rgb([255, 255, 255]));
+    assert_eq!(couleur("web-safe", 215), Rgb([255, 255, 255]));
+    assert_eq!(couleur("web-safe", 1), Rgb([0, 0, 51]));
+}
+
+#[test]
+fn les_noms_ignorent_la_casse_et_les_variantes() {
+    assert_eq!(preset(" PICO8 ").unwrap(), preset("pico8").unwrap());
+    assert_eq!(preset("websafe").unwrap(), preset("web-safe").unwrap());
+    assert_eq!(preset("greyscale-4").unwrap(), preset("grayscale-4").unwrap());
+    assert!(preset("amiga").is_none());
+}
+
+#[test]
+fn les_niveaux_de_gris_vont_de_2_a_256() {
+    for refuse in ["grayscale-0", "grayscale-1", "grayscale-257", "grayscale-", "grayscale-x"] {
+        assert!(preset(refuse).is_none(), "{}", refuse);
+    }
+    let deux = preset("grayscale-2").unwrap();
+    assert_eq!(deux.couleurs(), &[Rgb([0, 0, 0]), Rgb([255, 255, 255])]);
+    let tous = preset("grayscale-256").unwrap();
+    assert_eq!(tous.len(), 256);
+    assert!(tous.couleurs().iter().enumerate().all(|(i, couleur)| *couleur == Rgb([i as u8; 3])));
+    assert_eq!(preset("grayscale-5").unwrap().couleurs()[2], Rgb([128, 128, 128]));
+}
@@ -0,0 +1,119 @@
+//! Vérifie l'extraction automatique de palette.
+
+use std::collections::HashSet;
+
+use ditherpunk::{extraire_palette, passage_a_une_palette, Quantificateur};
+use image::{Rgb, RgbImage};
+
+const QUANTIFICATEURS: [Quantificateur; 4] =
+    [Quantificateur::MedianCut, Quantificateur::KMeans, Quantificateur::Octree, Quantificateur::Wu];
+
+/// Seize couleurs bien séparées, réparties sur les sommets et le centre du cube RGB.
+fn seize_couleurs() -> Vec<Rgb<u8>> {
+    let mut couleurs: Vec<Rgb<u8>> =
+        (0..8).map(|i| Rgb([0, 1, 2].map(|c| if i >> c & 1 == 1 { 240 } else { 15 }))).collect();
+    couleurs.extend((0..8).map(|i| Rgb([0, 1, 2].map(|c| if i >> c & 1 == 1 { 170 } else { 85 }))));
+    couleurs
+}
+
+/// Image en bandes verticales, une par couleur.
+fn bandes(couleurs: &[Rgb<u8>]) -> RgbImage {
+    RgbImage::from_fn(8 * couleurs.len() as u32, 8, |x, _| couleurs[x as usize / 8])
+}
+
+/// Dégradé de teintes, avec bien plus de couleurs distinctes que la palette demandée.
+fn degrade() -> RgbImage {
+    RgbImage::from_fn(64, 64, |x, y| Rgb([(x * 4) as u8, (y * 4) as u8, ((x + y) * 2) as u8]))
+}
+
+fn ensemble(couleurs: &[Rgb<u8>]) -> HashSet<Rgb<u8>> {
+    couleurs.iter().copied().collect()
+}
+
+#[test]
+fn une_image_de_n_couleurs_rend_exactement_ces_couleurs() {
+    let couleurs = seize_couleurs();
+    let img = bandes(&couleurs);
+    for methode in QUANTIFICATEURS {
+        for nombre in [16, 32] {
+            let palette = extraire_palette(&img, nombre, methode);
+            assert_eq!(palette.len(), 16, "{:?}, {}", methode, nombre);
+            assert_eq!(ensemble(palette.couleurs()), ensemble(&couleurs), "{:?}, {}", methode, nombre);
+        }
+    }
+}
+
+#[test]
+fn des_amas_bien_separes_donnent_leurs_centres() {
+    // Chaque couleur est déclinée en quelques variantes proches : 64 couleurs distinctes pour 16 amas.
+    let couleurs = seize_couleurs();
+    let img = RgbImage::from_fn(128, 32, |x, y| {
+        let Rgb([r, g, b]) = couleurs[x as usize / 8];
+        let (dx, dy) = ((x % 2) as u8 * 2, (y % 2) as u8 * 2);
+        Rgb([r + dx, g + dy, b])
+    });
+    for methode in QUANTIFICATEURS {
+        let palette = extraire_palette(&img, 16, methode);
+        assert_eq!(palette.len(), 16, "{:?}", methode);
+        for couleur in &couleurs {
+            let proche = palette.plus_proche(couleur);
+            let ecart = (0..3).map(|c| proche[c].abs_diff(couleur[c])).max().unwrap();
+            assert!(ecart <= 8, "{:?} : {:?} approchée par {:?}", methode, couleur, proche);
+        }
+    }
+}
+
+#[test]
+fn la_palette_ne_depasse_jamais_le_nombre_demande() {
+    let img = degrade();
+    for methode in QUANTIFICATEURS {
+        for nombre in [1, 2, 3, 7, 16, 64] {
+            let palette = extraire_palette(&img, nombre, methode);
+            assert!(!palette.is_empty() && palette.len() <= nombre, "{:?}, {} : {}", methode, nombre, palette.len());
+        }
+        assert!(extraire_palette(&img, 0, methode).is_empty(), "{:?}", methode);
+    }
+}
+
+#[test]
+fn seize_couleurs_restent_fideles_a_l_image() {
+    let img = degrade();
+    for methode in QUANTIFICATEURS {
+        let ecart_moyen = |nombre: usize| {
+            let resultat = passage_a_une_palette(&img, &extraire_palette(&img, nombre, methode));
+            let somme: f64 = img
+                .pixels()
+                .zip(resultat.pixels())
+                .map(|(a, b)| (0..3).map(|c| (a[c] as f64 - b[c] as f64).powi(2)).sum::<f64>().sqrt())
+                .sum();
+            somme / img.pixels().len() as f64
+        };
+        let (deux, seize) = (ecart_moyen(2), ecart_moyen(16));
+        assert!(seize < 40.0, "{:?} : écart moyen {}", methode, seize);
+        assert!(seize < deux / 2.0, "{:?} : {} avec 16 couleurs, {} avec 2", methode, seize, deux);
+    }
+}
+
+#[test]
+fn les_cas_limites_ne_paniquent_pas() {
+    let uni = RgbImage::from_pixel(10, 10, Rgb([12, 34, 56]));
+    let vide = RgbImage::new(0, 0);
+    for methode in QUANTIFICATEURS {
+        for nombre in [1, 16, 256] {
+            assert_eq!(extraire_palette(&uni, nombre, methode).couleurs(), &[Rgb([12, 34, 56])], "{:?}", methode);
+            assert!(extraire_palette(&vide, nombre, methode).is_empty(), "{:?}", methode);
+        }
+        let deux = bandes(&[Rgb([0, 0, 0]), Rgb([255, 255, 255])]);
+        assert_eq!(extraire_palette(&deux, 1, methode).len(), 1, "{:?}", methode);
+        assert_eq!(extraire_palette(&deux, 1000, methode).len(), 2, "{:?}", methode);
+    }
+}
+
+#[test]
+fn les_k_moyennes_sont_reproductibles() {
+    let img = degrade();
+    let premiere = extraire_palette(&img, 16, Quantificateur::KMeans);
+    for _ in 0..3 {
+        assert_eq!(extraire_palette(&img, 16, Quantificateur::KMeans).couleurs(), premiere.couleurs());
+    }
+}
@@ -0,0 +1,19 @@
+//! Vérifie le tramage de Riemersma.
+
+use ditherpunk::{tramage_riemersma, tramage_riemersma_palette, Palette, Reglages};
+use image::{Rgb, RgbImage};
+
+#[test]
+fn le_gris_moyen_est_conserve() {
+    for gris in [10u8, 64, 128, 200, 245] {
+        let img = RgbImage::from_pixel(61, 47, Rgb([gris, gris, gris]));
+        let total = (61 * 47) as f64;
+        let blancs = tramage_riemersma(&img, &Reglages::default()).pixels().filter(|pixel| pixel[0] == 255).count();
+        assert!((blancs as f64 / total - gris as f64 / 255.0).abs() < 0.01, "gris {} : {} blancs", gris, blancs);
+
+        let palette = Palette::new(vec![Rgb([0, 0, 0]), Rgb([255, 255, 255])]);
+        let resultat = tramage_riemersma_palette(&img, &palette, &Reglages::default());
+        let blancs = resultat.pixels().filter(|pixel| pixel[0] == 255).count();
+        assert!((blancs as f64 / total - gris as f64 / 255.0).abs() < 0.01, "gris {} : {} blancs", gris, blancs);
+    }
+}
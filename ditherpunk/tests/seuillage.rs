@@ -0,0 +1,71 @@
+//! Vérifie les seuils fixes, globaux et locaux du mode monochrome.
+
+use ditherpunk::{seuil_automatique, seuillage_par_paire, MethodeSeuil, Reglages};
+use image::{Rgb, RgbImage};
+
+const BLANC: Rgb<u8> = Rgb([255, 255, 255]);
+const NOIR: Rgb<u8> = Rgb([0, 0, 0]);
+
+fn gris(valeur: u8) -> Rgb<u8> {
+    Rgb([valeur, valeur, valeur])
+}
+
+/// Image à deux populations : un fond à 190 et des traits à 60.
+fn bimodale() -> RgbImage {
+    RgbImage::from_fn(40, 40, |x, y| if x % 5 == 0 || y % 7 == 0 { gris(60) } else { gris(190) })
+}
+
+#[test]
+fn les_seuils_se_lisent_en_ligne_de_commande() {
+    assert_eq!("100".parse(), Ok(MethodeSeuil::Fixe(100.0)));
+    assert_eq!("otsu".parse(), Ok(MethodeSeuil::Otsu));
+    assert_eq!("bradley".parse(), Ok(MethodeSeuil::Bradley));
+    assert!("300".parse::<MethodeSeuil>().is_err());
+    assert!("inconnu".parse::<MethodeSeuil>().is_err());
+}
+
+#[test]
+fn les_seuils_globaux_separent_les_deux_populations() {
+    let img = bimodale();
+    for methode in [MethodeSeuil::Otsu, MethodeSeuil::Kapur, MethodeSeuil::Moyenne] {
+        let seuil = seuil_automatique(&img, methode, &Reglages::default()).unwrap();
+        assert!((60.0..190.0).contains(&seuil), "{:?} : {}", methode, seuil);
+    }
+    assert_eq!(seuil_automatique(&img, MethodeSeuil::Mediane, &Reglages::default()), Some(190.0));
+    assert_eq!(seuil_automatique(&img, MethodeSeuil::Sauvola, &Reglages::default()), None);
+}
+
+#[test]
+fn le_seuil_fixe_garde_la_paire_de_couleurs() {
+    let img = RgbImage::from_fn(16, 1, |x, _| gris((x * 16) as u8));
+    let rouge = Rgb([255, 0, 0]);
+    let bleu = Rgb([0, 0, 255]);
+    let resultat = seuillage_par_paire(&img, rouge, bleu, MethodeSeuil::Fixe(100.0), 15, &Reglages::default());
+    for (x, _, pixel) in resultat.enumerate_pixels() {
+        assert_eq!(*pixel, if x * 16 > 100 { rouge } else { bleu });
+    }
+}
+
+#[test]
+fn les_seuils_locaux_resistent_a_un_eclairage_inegal() {
+    // Des traits plus sombres que leur voisinage, sur un fond qui s'assombrit de gauche à droite :
+    // aucun seuil global ne convient, mais un seuil local retrouve tous les traits.
+    let img = RgbImage::from_fn(120, 30, |x, y| {
+        let fond = 240 - x as u8;
+        if y % 6 == 0 {
+            gris(fond - 60)
+        } else {
+            gris(fond)
+        }
+    });
+    for methode in [MethodeSeuil::Sauvola, MethodeSeuil::Niblack, MethodeSeuil::Bradley] {
+        let resultat = seuillage_par_paire(&img, BLANC, NOIR, methode, 15, &Reglages::default());
+        for (x, y, pixel) in resultat.enumerate_pixels() {
+            if y % 6 == 0 {
+                assert_eq!(*pixel, NOIR, "{:?} trait en ({}, {})", methode, x, y);
+            }
+        }
+        let fond_blanc = resultat.enumerate_pixels().filter(|(_, y, pixel)| y % 6 != 0 && **pixel == BLANC).count();
+        assert!(fond_blanc > 120 * 25 * 9 / 10, "{:?} : {} pixels de fond blancs", methode, fond_blanc);
+    }
+}
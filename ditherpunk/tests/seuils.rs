@@ -0,0 +1,95 @@
+//! Vérifie les cartes de seuils : conversion des matrices de Bayer, trames à points groupés, bruit bleu
+//! et chargement d'images.
+
+use ditherpunk::{CarteSeuils, FormeTrame, MatriceBayer, MatriceTrame};
+use image::{GrayImage, Luma};
+
+fn seuils(carte: &CarteSeuils) -> Vec<f32> {
+    (0..carte.hauteur as u32).flat_map(|y| (0..carte.largeur as u32).map(move |x| carte.seuil(x, y))).collect()
+}
+
+#[test]
+fn une_matrice_de_bayer_garde_ses_seuils() {
+    let bayer = MatriceBayer::avec_taille(6).unwrap();
+    let carte = CarteSeuils::from(&bayer);
+    assert_eq!(carte.niveaux, 36);
+    for y in 0..12 {
+        for x in 0..12 {
+            assert_eq!(carte.seuil(x, y), bayer.seuil(x, y));
+        }
+    }
+}
+
+#[test]
+fn chaque_trame_est_une_permutation_des_rangs() {
+    for forme in [FormeTrame::Spirale, FormeTrame::Diagonale, FormeTrame::Point45, FormeTrame::Lignes] {
+        for taille in [1, 4, 7, 16] {
+            let trame = MatriceTrame::new(forme, taille).unwrap();
+            let mut rangs: Vec<u32> = trame.matrice.iter().flatten().copied().collect();
+            rangs.sort();
+            assert_eq!(rangs, (0..(taille * taille) as u32).collect::<Vec<_>>(), "{:?} {}", forme, taille);
+        }
+    }
+    assert!(MatriceTrame::new(FormeTrame::Spirale, 0).is_err());
+    assert!(MatriceTrame::new(FormeTrame::Spirale, 257).is_err());
+}
+
+#[test]
+fn les_points_grossissent_depuis_le_centre() {
+    let spirale = MatriceTrame::new(FormeTrame::Spirale, 4).unwrap();
+    assert_eq!(spirale.matrice, vec![vec![6, 7, 8, 9], vec![5, 0, 1, 10], vec![4, 3, 2, 11], vec![15, 14, 13, 12]]);
+
+    // Les 12 premières cases allumées d'une trame en losange forment un seul point, autour du centre.
+    let diagonale = MatriceTrame::new(FormeTrame::Diagonale, 8).unwrap();
+    for (y, ligne) in diagonale.matrice.iter().enumerate() {
+        for (x, &rang) in ligne.iter().enumerate() {
+            let distance = (x as f32 + 0.5 - 4.0).abs() + (y as f32 + 0.5 - 4.0).abs();
+            assert_eq!(rang < 12, distance < 3.0, "case ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn le_bruit_bleu_utilise_chaque_seuil_une_fois() {
+    let carte = CarteSeuils::bruit_bleu(16, 0).unwrap();
+    let mut valeurs = seuils(&carte);
+    valeurs.sort_by(f32::total_cmp);
+    let attendus: Vec<f32> = (0..256).map(|rang| (rang as f32 + 0.5) / 256.0).collect();
+    assert_eq!(valeurs, attendus);
+}
+
+#[test]
+fn le_bruit_bleu_depend_de_la_graine() {
+    let carte = CarteSeuils::bruit_bleu(16, 1).unwrap();
+    assert_eq!(carte, CarteSeuils::bruit_bleu(16, 1).unwrap());
+    assert_ne!(carte, CarteSeuils::bruit_bleu(16, 2).unwrap());
+    assert!(CarteSeuils::bruit_bleu(0, 0).is_err());
+    assert!(CarteSeuils::bruit_bleu(129, 0).is_err());
+}
+
+#[test]
+fn le_bruit_bleu_n_a_pas_d_amas() {
+    // À 50 %, aucun pixel allumé ne doit avoir ses quatre voisins allumés aussi.
+    let carte = CarteSeuils::bruit_bleu(32, 0).unwrap();
+    let allume = |x: i32, y: i32| carte.seuil(x.rem_euclid(32) as u32, y.rem_euclid(32) as u32) < 0.5;
+    for y in 0..32 {
+        for x in 0..32 {
+            let voisins = [(-1, 0), (1, 0), (0, -1), (0, 1)].iter().filter(|(dx, dy)| allume(x + dx, y + dy)).count();
+            assert!(!(allume(x, y) && voisins == 4), "amas en ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn une_image_png_devient_une_carte_de_seuils() {
+    let image = GrayImage::from_fn(5, 3, |x, y| Luma([(x * 50 + y) as u8]));
+    let chemin = std::env::temp_dir().join("ditherpunk_carte_seuils.png");
+    image.save(&chemin).unwrap();
+
+    let carte = CarteSeuils::charger(&chemin).unwrap();
+    std::fs::remove_file(&chemin).unwrap();
+    assert_eq!((carte.largeur, carte.hauteur, carte.niveaux), (5, 3, 15));
+    assert_eq!(carte, CarteSeuils::depuis_image(&image).unwrap());
+    assert_eq!(carte.seuil(2, 1), 101.5 / 256.0);
+    assert_eq!(carte.seuil(7, 4), carte.seuil(2, 1));
+}
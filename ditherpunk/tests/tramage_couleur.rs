@@ -0,0 +1,91 @@
+//! Vérifie les tramages ordonné, aléatoire et par motif vers une palette.
+
+use ditherpunk::{
+    ecart_par_defaut, monochrome_par_paire, ordered_dithering_motif, ordered_dithering_palette, tramage_random_palette,
+    Bruit, CarteSeuils, Palette, Reglages,
+};
+use image::{Rgb, RgbImage};
+
+const NOIR: Rgb<u8> = Rgb([0, 0, 0]);
+const BLANC: Rgb<u8> = Rgb([255, 255, 255]);
+
+/// Les huit sommets du cube RGB.
+fn sommets() -> Palette {
+    Palette::new((0..8).map(|i| Rgb([0, 1, 2].map(|c| if i >> c & 1 == 1 { 255 } else { 0 }))).collect())
+}
+
+fn proportion_de_blanc(img: &RgbImage) -> f64 {
+    img.pixels().filter(|&&pixel| pixel == BLANC).count() as f64 / img.pixels().len() as f64
+}
+
+#[test]
+fn l_ecart_par_defaut_suit_la_racine_cubique_du_nombre_de_couleurs() {
+    assert_eq!(ecart_par_defaut(&Palette::new(vec![])), 255.0);
+    assert_eq!(ecart_par_defaut(&Palette::new(vec![NOIR])), 255.0);
+    assert_eq!(ecart_par_defaut(&sommets()), 127.5);
+    let palette = Palette::new((0..27).map(|i| Rgb([i, i, i])).collect());
+    assert!((ecart_par_defaut(&palette) - 85.0).abs() < 1e-3);
+}
+
+#[test]
+fn une_couleur_de_la_palette_reste_inchangee() {
+    let palette = sommets();
+    let ecart = ecart_par_defaut(&palette);
+    for &couleur in palette.couleurs() {
+        let img = RgbImage::from_pixel(16, 16, couleur);
+        let ordonne = ordered_dithering_palette(&img, &palette, &CarteSeuils::default(), ecart, &Reglages::default());
+        assert_eq!(ordonne, img, "ordonné, {:?}", couleur);
+        let aleatoire = tramage_random_palette(&img, &palette, Bruit::Uniforme, Some(7), ecart, &Reglages::default());
+        assert_eq!(aleatoire, img, "aléatoire, {:?}", couleur);
+    }
+
+    let palette = Palette::new(vec![NOIR, BLANC, Rgb([200, 30, 40]), Rgb([20, 120, 220]), Rgb([240, 220, 0])]);
+    for lineaire in [false, true] {
+        let reglages = Reglages { lineaire, ..Default::default() };
+        for &couleur in palette.couleurs() {
+            let img = RgbImage::from_pixel(16, 16, couleur);
+            let motif = ordered_dithering_motif(&img, &palette, &CarteSeuils::default(), &reglages);
+            assert_eq!(motif, img, "motif, {:?}, linéaire {}", couleur, lineaire);
+        }
+    }
+}
+
+#[test]
+fn la_proportion_de_blanc_suit_le_gris() {
+    let palette = Palette::new(vec![NOIR, BLANC]);
+    for gris in [32u8, 64, 128, 192, 224] {
+        let img = RgbImage::from_pixel(64, 64, Rgb([gris, gris, gris]));
+        let attendu = gris as f64 / 255.0;
+
+        let ordonne = ordered_dithering_palette(&img, &palette, &CarteSeuils::default(), 255.0, &Reglages::default());
+        assert!((proportion_de_blanc(&ordonne) - attendu).abs() <= 1.0 / 64.0, "ordonné, gris {}", gris);
+
+        let aleatoire = tramage_random_palette(&img, &palette, Bruit::Uniforme, Some(3), 255.0, &Reglages::default());
+        assert!((proportion_de_blanc(&aleatoire) - attendu).abs() < 0.03, "aléatoire, gris {}", gris);
+
+        let motif = ordered_dithering_motif(&img, &palette, &CarteSeuils::default(), &Reglages::default());
+        assert!((proportion_de_blanc(&motif) - attendu).abs() <= 1.0 / 16.0, "motif, gris {}", gris);
+    }
+}
+
+#[test]
+fn le_tramage_par_motif_melange_les_couleurs_voisines() {
+    let palette = Palette::new(vec![NOIR, BLANC, Rgb([255, 0, 0]), Rgb([0, 0, 255])]);
+    let img = RgbImage::from_pixel(16, 16, Rgb([128, 0, 128]));
+    let resultat = ordered_dithering_motif(&img, &palette, &CarteSeuils::default(), &Reglages::default());
+    let rouges = resultat.pixels().filter(|&&pixel| pixel == Rgb([255, 0, 0])).count();
+    let bleus = resultat.pixels().filter(|&&pixel| pixel == Rgb([0, 0, 255])).count();
+    assert!(rouges > 0 && bleus > 0);
+    assert!(resultat.pixels().all(|pixel| pixel[1] == 0));
+}
+
+#[test]
+fn le_seuil_du_monochrome_est_celui_demande() {
+    let img = RgbImage::from_fn(256, 1, |x, _| Rgb([x as u8, x as u8, x as u8]));
+    for seuil in [0.0, 64.0, 128.0, 200.0] {
+        let resultat = monochrome_par_paire(&img, BLANC, NOIR, seuil, &Reglages::default());
+        for (x, _, pixel) in resultat.enumerate_pixels() {
+            assert_eq!(*pixel, if x as f32 > seuil { BLANC } else { NOIR }, "seuil {}, x {}", seuil, x);
+        }
+    }
+}
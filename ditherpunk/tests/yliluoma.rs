@@ -0,0 +1,80 @@
+//! Vérifie le tramage positionnel de Yliluoma.
+
+use ditherpunk::{tramage_yliluoma, AlgorithmeYliluoma, CarteSeuils, Distance, Palette, Reglages, TAILLE_MAX_CANDIDATS};
+use image::{Rgb, RgbImage};
+
+const ALGORITHMES: [AlgorithmeYliluoma; 3] = [AlgorithmeYliluoma::Un, AlgorithmeYliluoma::Deux, AlgorithmeYliluoma::Trois];
+
+const DISTANCES: [Distance; 6] = [
+    Distance::Euclidienne,
+    Distance::Redmean,
+    Distance::DeltaE76,
+    Distance::DeltaE94,
+    Distance::Ciede2000,
+    Distance::Oklab,
+];
+
+fn proportion(img: &RgbImage, couleur: Rgb<u8>) -> f64 {
+    img.pixels().filter(|&&pixel| pixel == couleur).count() as f64 / img.pixels().len() as f64
+}
+
+#[test]
+fn le_gris_moyen_melange_noir_et_blanc_a_parts_egales() {
+    let (noir, blanc) = (Rgb([0, 0, 0]), Rgb([255, 255, 255]));
+    let img = RgbImage::from_pixel(16, 16, Rgb([128, 128, 128]));
+    for distance in DISTANCES {
+        let palette = Palette::new(vec![noir, blanc]).avec_distance(distance);
+        for algorithme in ALGORITHMES {
+            let resultat = tramage_yliluoma(&img, &palette, &CarteSeuils::default(), algorithme, &Reglages::default());
+            // L'algorithme 2 construit son plan couleur par couleur, à un 64e près.
+            let tolerance = if algorithme == AlgorithmeYliluoma::Deux { 1.0 / 64.0 } else { 0.0 };
+            assert!((proportion(&resultat, blanc) - 0.5).abs() <= tolerance, "{:?}, {:?}", distance, algorithme);
+            assert!((proportion(&resultat, noir) - 0.5).abs() <= tolerance, "{:?}, {:?}", distance, algorithme);
+        }
+    }
+}
+
+#[test]
+fn l_algorithme_2_reproduit_la_proportion_du_gris() {
+    let (noir, blanc) = (Rgb([0, 0, 0]), Rgb([255, 255, 255]));
+    let palette = Palette::new(vec![noir, blanc]);
+    for gris in [32u8, 64, 96, 160, 224] {
+        let img = RgbImage::from_pixel(16, 16, Rgb([gris, gris, gris]));
+        let resultat =
+            tramage_yliluoma(&img, &palette, &CarteSeuils::default(), AlgorithmeYliluoma::Deux, &Reglages::default());
+        assert!((proportion(&resultat, blanc) - gris as f64 / 255.0).abs() <= 1.0 / 64.0, "gris {}", gris);
+    }
+}
+
+#[test]
+fn les_couleurs_de_la_palette_restent_inchangees() {
+    let couleurs = vec![Rgb([0, 0, 0]), Rgb([255, 255, 255]), Rgb([200, 30, 40]), Rgb([20, 120, 220]), Rgb([240, 220, 0])];
+    for distance in DISTANCES {
+        let palette = Palette::new(couleurs.clone()).avec_distance(distance);
+        for &couleur in &couleurs {
+            let img = RgbImage::from_pixel(8, 8, couleur);
+            for algorithme in ALGORITHMES {
+                let resultat =
+                    tramage_yliluoma(&img, &palette, &CarteSeuils::default(), algorithme, &Reglages::default());
+                assert_eq!(resultat, img, "{:?}, {:?}, {:?}", couleur, distance, algorithme);
+            }
+        }
+    }
+}
+
+#[test]
+fn une_grande_palette_ne_melange_que_les_couleurs_proches() {
+    let couleurs: Vec<Rgb<u8>> = (0..=255).map(|gris| Rgb([gris, gris, 255 - gris])).collect();
+    assert!(couleurs.len() > TAILLE_MAX_CANDIDATS);
+    let palette = Palette::new(couleurs.clone());
+    let img = RgbImage::from_fn(64, 4, |x, y| couleurs[(x * 4 + y) as usize]);
+    for algorithme in [AlgorithmeYliluoma::Un, AlgorithmeYliluoma::Trois] {
+        let resultat = tramage_yliluoma(&img, &palette, &CarteSeuils::default(), algorithme, &Reglages::default());
+        assert_eq!(resultat, img, "{:?}", algorithme);
+    }
+
+    let img = RgbImage::from_pixel(16, 16, Rgb([128, 128, 128]));
+    let resultat =
+        tramage_yliluoma(&img, &palette, &CarteSeuils::default(), AlgorithmeYliluoma::Un, &Reglages::default());
+    assert!(resultat.pixels().all(|pixel| pixel[0].abs_diff(128) <= 16 && pixel[2].abs_diff(127) <= 16));
+}